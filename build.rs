@@ -1,15 +1,36 @@
 const COMMANDS: &[&str] = &[
+    "create_session",
+    "destroy_session",
     "initialize_session",
     "set_metadata",
     "set_playback_info",
     "set_playback_status",
     "set_position",
     "clear_metadata",
+    "set_capabilities",
+    "set_track_list",
+    "add_track",
+    "remove_track",
+    "update_track_metadata",
+    "set_playlists",
     "get_metadata",
     "get_playback_info",
     "get_playback_status",
     "get_position",
+    "get_system_metadata",
+    "get_system_playback_info",
+    "get_system_playback_status",
+    "get_system_position",
     "is_enabled",
+    "get_track_list",
+    "list_sessions",
+    "control_session",
+    "watch_sessions",
+    "unwatch_sessions",
+    "subscribe_events",
+    "unsubscribe",
+    "next",
+    "previous",
 ];
 
 fn main() {
@@ -1,4 +1,6 @@
-import { invoke } from '@tauri-apps/api/core'
+import { Channel, invoke } from '@tauri-apps/api/core'
+import { listen, type UnlistenFn } from '@tauri-apps/api/event'
+import { getCurrentWindow } from '@tauri-apps/api/window'
 
 // Media metadata interface
 export interface MediaMetadata {
@@ -9,6 +11,32 @@ export interface MediaMetadata {
   duration?: number; // Duration in seconds
   artworkUrl?: string;
   artworkData?: string; // Base64 encoded image data
+  trackId?: string; // Id of this track in the published track list
+  artists?: string[]; // All artists, takes precedence over artist
+  albumArtists?: string[]; // All album artists, takes precedence over albumArtist
+  genres?: string[];
+  trackNumber?: number;
+  discNumber?: number;
+  composers?: string[];
+  lyricists?: string[];
+  releaseDate?: string; // ISO 8601 date or date-time
+  url?: string; // Location of the media itself
+  comment?: string;
+  userRating?: number; // From 0.0 to 1.0
+  playCount?: number;
+}
+
+// Track list entry
+export interface Track {
+  id: string; // Stable id chosen by the application
+  metadata: MediaMetadata;
+}
+
+// Playlist entry
+export interface Playlist {
+  id: string; // Stable id chosen by the application
+  name: string;
+  icon?: string; // URI of the playlist icon
 }
 
 // Playback status enum
@@ -32,8 +60,60 @@ export interface PlaybackInfo {
   shuffle: boolean;
   repeatMode: RepeatMode;
   playbackRate: number;
+  volume?: number; // Volume from 0.0 to 1.0, defaults to 1.0
+}
+
+// Actions offered to the system media UI, all enabled by default
+export interface MediaCapabilities {
+  canGoNext?: boolean;
+  canGoPrevious?: boolean;
+  canPlay?: boolean;
+  canPause?: boolean;
+  canSeek?: boolean;
+  canControl?: boolean; // When false, every other action is unavailable too
+}
+
+// A media session published by any application on the system
+export interface SystemMediaSession {
+  id: string; // MPRIS bus name on Linux
+  identity?: string; // Human readable player name
+  desktopEntry?: string; // Desktop file name without extension
+  status: PlaybackStatus;
+  position: number; // Position in seconds
+  metadata?: MediaMetadata;
+  capabilities: MediaCapabilities;
+}
+
+// Fields of a system media session that changed, only changed fields are set
+export interface SessionChange {
+  id: string;
+  status?: PlaybackStatus;
+  position?: number; // Position in seconds after a seek
+  metadata?: MediaMetadata;
+  capabilities?: MediaCapabilities;
+}
+
+// Callbacks for watchSessions
+export interface SessionWatcher {
+  onAdded?: (session: SystemMediaSession) => void;
+  onRemoved?: (id: string) => void;
+  onChanged?: (change: SessionChange) => void;
 }
 
+// Command sent to another application's media session
+export type SessionAction =
+  | 'play'
+  | 'pause'
+  | 'playPause'
+  | 'stop'
+  | 'next'
+  | 'previous'
+  | { seekBy: number } // Relative offset in seconds
+  | { setPosition: number } // Absolute position in seconds
+  | { setVolume: number } // Volume from 0.0 to 1.0
+  | { setShuffle: boolean }
+  | { setRepeatMode: RepeatMode };
+
 // Media control event types
 export enum MediaControlEventType {
   Play = 'play',
@@ -45,98 +125,333 @@ export enum MediaControlEventType {
   FastForward = 'fastForward',
   Rewind = 'rewind',
   SeekTo = 'seekTo',
+  SeekBy = 'seekBy',
   SetPosition = 'setPosition',
   SetPlaybackRate = 'setPlaybackRate',
+  SetShuffle = 'setShuffle',
+  SetRepeatMode = 'setRepeatMode',
+  SetVolume = 'setVolume',
+  GoTo = 'goTo',
+  AddTrack = 'addTrack',
+  RemoveTrack = 'removeTrack',
+  ActivatePlaylist = 'activatePlaylist',
+  Raise = 'raise',
+  Quit = 'quit',
+  OpenUri = 'openUri',
 }
 
-// Media control event interface
+// Media control event interface. `data` carries the argument of events that
+// have one, such as the offset of seekBy or the URI of openUri
 export interface MediaControlEvent {
   eventType: MediaControlEventType;
   timestamp: number;
   data?: any;
+  sessionId?: string; // Session the event is addressed to
+}
+
+// Id of the session every application has, used when no session id is given
+export const DEFAULT_SESSION_ID = 'default';
+
+// Create an additional media session alongside the default one. Only Linux
+// supports more than one; elsewhere this rejects with `unsupported`.
+export async function createSession(sessionId: string): Promise<void> {
+  await invoke('plugin:media|create_session', { sessionId });
+}
+
+// Remove a session created with createSession
+export async function destroySession(sessionId: string): Promise<void> {
+  await invoke('plugin:media|destroy_session', { sessionId });
+}
+
+// Tauri event carrying every incoming media control event
+export const CONTROL_EVENT = 'media://control';
+
+// Call `handler` for every media control event delivered to this window, such
+// as a media key press. Events of sessions bound to another window are not.
+// The returned function stops listening.
+export async function onMediaControl(
+  handler: (event: MediaControlEvent) => void
+): Promise<UnlistenFn> {
+  // Listeners without a target would also receive events sent to other windows
+  const target = { kind: 'Window' as const, label: getCurrentWindow().label };
+  return await listen<MediaControlEvent>(CONTROL_EVENT, (e) => handler(e.payload), { target });
+}
+
+// Stream media control events to `handler` over a channel owned by this webview.
+// Resolves to a subscription id for unsubscribe; the subscription also ends
+// when the page reloads or its window closes.
+export async function subscribeEvents(
+  handler: (event: MediaControlEvent) => void
+): Promise<number> {
+  const channel = new Channel<MediaControlEvent>();
+  channel.onmessage = handler;
+  return await invoke('plugin:media|subscribe_events', { channel });
+}
+
+// End a subscription created by subscribeEvents
+export async function unsubscribe(id: number): Promise<void> {
+  await invoke('plugin:media|unsubscribe', { id });
+}
+
+// Machine-readable failure types of the plugin's commands
+export type MediaErrorCode =
+  | 'not_initialized'
+  | 'bus_unavailable'
+  | 'name_taken'
+  | 'invalid_argument'
+  | 'unsupported'
+  | 'session_not_found'
+  | 'no_active_session'
+  | 'platform';
+
+// Shape of every error a command rejects with
+export interface MediaError {
+  code: MediaErrorCode;
+  message: string;
+  // Variant-specific fields: `name` for name_taken, `field` and `reason` for
+  // invalid_argument, `feature` for unsupported, `sessionId` for session_not_found
+  details: Record<string, string> | null;
+}
+
+// Optional session settings, overriding the plugin config
+export interface SessionOptions {
+  supportedUriSchemes?: string[]; // Schemes accepted by OpenUri requests
+  supportedMimeTypes?: string[];
+  // Tie the session to the calling window: its events only reach that window,
+  // and closing the window stops the session and releases it
+  bindToWindow?: boolean;
+}
+
+// Details about the created session
+export interface MediaSessionInfo {
+  busName?: string; // MPRIS bus name that was acquired, Linux only
 }
 
 // Initialize the media session
-export async function initializeSession(appId: string, appName: string): Promise<void> {
-  await invoke('plugin:media|initialize_session', {
+export async function initializeSession(
+  appId: string,
+  appName: string,
+  options?: SessionOptions,
+  sessionId?: string,
+): Promise<MediaSessionInfo> {
+  return await invoke('plugin:media|initialize_session', {
     request: {
       appId,
       appName,
+      ...options,
     },
+    sessionId,
   });
 }
 
 // Set media metadata
-export async function setMetadata(metadata: MediaMetadata): Promise<void> {
+export async function setMetadata(metadata: MediaMetadata, sessionId?: string): Promise<void> {
   await invoke('plugin:media|set_metadata', {
     metadata,
+    sessionId,
   });
 }
 
 // Set playback information
-export async function setPlaybackInfo(info: PlaybackInfo): Promise<void> {
+export async function setPlaybackInfo(info: PlaybackInfo, sessionId?: string): Promise<void> {
   await invoke('plugin:media|set_playback_info', {
     info,
+    sessionId,
   });
 }
 
 // Set playback status
-export async function setPlaybackStatus(status: PlaybackStatus): Promise<void> {
+export async function setPlaybackStatus(status: PlaybackStatus, sessionId?: string): Promise<void> {
   await invoke('plugin:media|set_playback_status', {
     status,
+    sessionId,
   });
 }
 
 // Set current playback position
-export async function setPosition(position: number): Promise<void> {
+export async function setPosition(position: number, sessionId?: string): Promise<void> {
   await invoke('plugin:media|set_position', {
     position,
+    sessionId,
   });
 }
 
 // Clear all metadata
-export async function clearMetadata(): Promise<void> {
-  await invoke('plugin:media|clear_metadata');
+export async function clearMetadata(sessionId?: string): Promise<void> {
+  await invoke('plugin:media|clear_metadata', { sessionId });
 }
 
-// Get current metadata
-export async function getMetadata(): Promise<MediaMetadata | null> {
-  return await invoke('plugin:media|get_metadata');
+// Enable or disable individual actions in the system media UI
+export async function setCapabilities(capabilities: MediaCapabilities, sessionId?: string): Promise<void> {
+  await invoke('plugin:media|set_capabilities', {
+    capabilities,
+    sessionId,
+  });
 }
 
-// Get current playback info
-export async function getPlaybackInfo(): Promise<PlaybackInfo | null> {
-  return await invoke('plugin:media|get_playback_info');
+// Publish the ordered track list
+export async function setTrackList(tracks: Track[], sessionId?: string): Promise<void> {
+  await invoke('plugin:media|set_track_list', {
+    tracks,
+    sessionId,
+  });
 }
 
-// Get current playback status
-export async function getPlaybackStatus(): Promise<PlaybackStatus> {
-  return await invoke('plugin:media|get_playback_status');
+// Insert a track after another one, or at the start of the list
+export async function addTrack(
+  track: Track,
+  afterTrackId?: string,
+  sessionId?: string,
+): Promise<void> {
+  await invoke('plugin:media|add_track', {
+    track,
+    afterTrackId,
+    sessionId,
+  });
 }
 
-// Get current position
-export async function getPosition(): Promise<number> {
-  return await invoke('plugin:media|get_position');
+// Remove a track from the track list
+export async function removeTrack(trackId: string, sessionId?: string): Promise<void> {
+  await invoke('plugin:media|remove_track', {
+    trackId,
+    sessionId,
+  });
+}
+
+// Replace the metadata of a listed track
+export async function updateTrackMetadata(
+  trackId: string,
+  metadata: MediaMetadata,
+  sessionId?: string,
+): Promise<void> {
+  await invoke('plugin:media|update_track_metadata', {
+    trackId,
+    metadata,
+    sessionId,
+  });
+}
+
+// Publish the available playlists and the active one
+export async function setPlaylists(
+  playlists: Playlist[],
+  activePlaylistId?: string,
+  sessionId?: string,
+): Promise<void> {
+  await invoke('plugin:media|set_playlists', {
+    playlists,
+    activePlaylistId,
+    sessionId,
+  });
+}
+
+// Get the published track list
+export async function getTrackList(sessionId?: string): Promise<Track[]> {
+  return await invoke('plugin:media|get_track_list', { sessionId });
+}
+
+// List every media session on the system, including this app's own
+export async function listSessions(): Promise<SystemMediaSession[]> {
+  return await invoke('plugin:media|list_sessions');
+}
+
+// Send a command to another media session, identified by its id from listSessions.
+// A null id targets the system's active session.
+export async function controlSession(
+  sessionId: string | null,
+  action: SessionAction
+): Promise<void> {
+  await invoke('plugin:media|control_session', {
+    sessionId,
+    action,
+  });
+}
+
+// Follow other media sessions as they appear, disappear and change.
+// The returned function stops watching.
+export async function watchSessions(watcher: SessionWatcher): Promise<UnlistenFn> {
+  const unlisteners = await Promise.all([
+    listen<SystemMediaSession>('media://session-added', (e) => watcher.onAdded?.(e.payload)),
+    listen<SessionChange>('media://session-removed', (e) => watcher.onRemoved?.(e.payload.id)),
+    listen<SessionChange>('media://session-changed', (e) => watcher.onChanged?.(e.payload)),
+  ]);
+  await invoke('plugin:media|watch_sessions');
+
+  // The watcher is shared and counted on the Rust side, so each caller
+  // releases only its own reference, and only once
+  let watching = true;
+  return async () => {
+    if (!watching) {
+      return;
+    }
+    watching = false;
+    unlisteners.forEach((unlisten) => unlisten());
+    await invoke('plugin:media|unwatch_sessions');
+  };
+}
+
+// Get the metadata a session of this application published
+export async function getMetadata(sessionId?: string): Promise<MediaMetadata | null> {
+  return await invoke('plugin:media|get_metadata', { sessionId });
+}
+
+// Get the playback info a session of this application published
+export async function getPlaybackInfo(sessionId?: string): Promise<PlaybackInfo | null> {
+  return await invoke('plugin:media|get_playback_info', { sessionId });
+}
+
+// Get the playback status of a session of this application
+export async function getPlaybackStatus(sessionId?: string): Promise<PlaybackStatus> {
+  return await invoke('plugin:media|get_playback_status', { sessionId });
+}
+
+// Get the playback position of a session of this application
+export async function getPosition(sessionId?: string): Promise<number> {
+  return await invoke('plugin:media|get_position', { sessionId });
+}
+
+// Get the metadata of the system's active media session
+export async function getSystemMetadata(): Promise<MediaMetadata | null> {
+  return await invoke('plugin:media|get_system_metadata');
+}
+
+// Get the playback info of the system's active media session
+export async function getSystemPlaybackInfo(): Promise<PlaybackInfo | null> {
+  return await invoke('plugin:media|get_system_playback_info');
+}
+
+// Get the playback status of the system's active media session
+export async function getSystemPlaybackStatus(): Promise<PlaybackStatus> {
+  return await invoke('plugin:media|get_system_playback_status');
+}
+
+// Get the playback position of the system's active media session
+export async function getSystemPosition(): Promise<number> {
+  return await invoke('plugin:media|get_system_position');
 }
 
 // Check if media controls are enabled
-export async function isEnabled(): Promise<boolean> {
-  return await invoke('plugin:media|is_enabled');
+export async function isEnabled(sessionId?: string): Promise<boolean> {
+  return await invoke('plugin:media|is_enabled', { sessionId });
 }
 
 // Media controls class for easier usage
 export class MediaControls {
   private initialized = false;
+  private unlistenControl: Promise<UnlistenFn> | null = null;
 
-  async initialize(appId: string, appName: string): Promise<void> {
-    if (this.initialized) return;
+  // Controls the default session unless another session id is given
+  constructor(private readonly sessionId?: string) {}
+
+  async initialize(appId: string, appName: string, options?: SessionOptions): Promise<MediaSessionInfo | null> {
+    if (this.initialized) return null;
     
-    await initializeSession(appId, appName);
+    const info = await initializeSession(appId, appName, options, this.sessionId);
     this.initialized = true;
+    return info;
   }
 
   async updateNowPlaying(metadata: MediaMetadata, info?: Partial<PlaybackInfo>): Promise<void> {
-    await setMetadata(metadata);
+    await setMetadata(metadata, this.sessionId);
     
     if (info) {
       const fullInfo: PlaybackInfo = {
@@ -145,25 +460,26 @@ export class MediaControls {
         shuffle: info.shuffle || false,
         repeatMode: info.repeatMode || RepeatMode.None,
         playbackRate: info.playbackRate || 1.0,
+        volume: info.volume ?? 1.0,
       };
-      await setPlaybackInfo(fullInfo);
+      await setPlaybackInfo(fullInfo, this.sessionId);
     }
   }
 
   async play(): Promise<void> {
-    await setPlaybackStatus(PlaybackStatus.Playing);
+    await setPlaybackStatus(PlaybackStatus.Playing, this.sessionId);
   }
 
   async pause(): Promise<void> {
-    await setPlaybackStatus(PlaybackStatus.Paused);
+    await setPlaybackStatus(PlaybackStatus.Paused, this.sessionId);
   }
 
   async stop(): Promise<void> {
-    await setPlaybackStatus(PlaybackStatus.Stopped);
+    await setPlaybackStatus(PlaybackStatus.Stopped, this.sessionId);
   }
 
   async togglePlayPause(): Promise<void> {
-    const status = await getPlaybackStatus();
+    const status = await getPlaybackStatus(this.sessionId);
     if (status === PlaybackStatus.Playing) {
       await this.pause();
     } else {
@@ -172,56 +488,79 @@ export class MediaControls {
   }
 
   async next(): Promise<void> {
-    await invoke('plugin:media|next');
+    await invoke('plugin:media|next', { sessionId: this.sessionId });
   }
 
   async previous(): Promise<void> {
-    await invoke('plugin:media|previous');
+    await invoke('plugin:media|previous', { sessionId: this.sessionId });
   }
 
   async setPosition(position: number): Promise<void> {
-    await setPosition(position);
+    await setPosition(position, this.sessionId);
   }
 
   async updatePosition(position: number): Promise<void> {
-    await setPosition(position);
+    await setPosition(position, this.sessionId);
   }
 
   async updatePlaybackStatus(status: PlaybackStatus): Promise<void> {
-    await setPlaybackStatus(status);
+    await setPlaybackStatus(status, this.sessionId);
   }
 
   async clearNowPlaying(): Promise<void> {
-    await clearMetadata();
+    await clearMetadata(this.sessionId);
   }
 
   async getMetadata(): Promise<MediaMetadata | null> {
-    return await invoke('plugin:media|get_metadata');
+    return await getMetadata(this.sessionId);
   }
 
   async getPlaybackInfo(): Promise<PlaybackInfo | null> {
-    return await invoke('plugin:media|get_playback_info');
+    return await getPlaybackInfo(this.sessionId);
   }
 
   async getPlaybackStatus(): Promise<PlaybackStatus> {
-    return await invoke('plugin:media|get_playback_status');
+    return await getPlaybackStatus(this.sessionId);
   }
 
   async getPosition(): Promise<number> {
-    return await invoke('plugin:media|get_position');
+    return await getPosition(this.sessionId);
+  }
+
+  async getSystemMetadata(): Promise<MediaMetadata | null> {
+    return await getSystemMetadata();
+  }
+
+  async getSystemPlaybackInfo(): Promise<PlaybackInfo | null> {
+    return await getSystemPlaybackInfo();
+  }
+
+  async getSystemPlaybackStatus(): Promise<PlaybackStatus> {
+    return await getSystemPlaybackStatus();
+  }
+
+  async getSystemPosition(): Promise<number> {
+    return await getSystemPosition();
   }
 
   async isEnabled(): Promise<boolean> {
-    return await invoke('plugin:media|is_enabled');
+    return await isEnabled(this.sessionId);
   }
 
+  // Replaces the handler for this session's events; null stops listening
   setEventHandler(handler: ((event: MediaControlEvent) => void) | null): void {
-    // This would need actual event listener implementation
-    console.warn('Event handler not yet implemented');
+    const previous = this.unlistenControl;
+    const sessionId = this.sessionId ?? DEFAULT_SESSION_ID;
+    this.unlistenControl = handler
+      ? onMediaControl((event) => {
+          if ((event.sessionId ?? DEFAULT_SESSION_ID) === sessionId) handler(event);
+        })
+      : null;
+    previous?.then((unlisten) => unlisten());
   }
 
   async clear(): Promise<void> {
-    await clearMetadata();
+    await clearMetadata(this.sessionId);
   }
 }
 
@@ -1,86 +1,283 @@
-use tauri::{command, AppHandle, Runtime};
+use tauri::{command, ipc::Channel, AppHandle, Runtime, Webview};
 
 use crate::models::*;
 use crate::MediaExt;
+use crate::MediaSession;
 use crate::Result;
 
+/// The session a command addresses, the default one when no id is given.
+fn session<R: Runtime>(app: &AppHandle<R>, session_id: Option<String>) -> MediaSession<'_, R> {
+    app.media()
+        .session(session_id.unwrap_or_else(|| DEFAULT_SESSION_ID.to_string()))
+}
+
+#[command]
+pub(crate) async fn create_session<R: Runtime>(
+    app: AppHandle<R>,
+    session_id: String,
+) -> Result<()> {
+    app.media().create_session(session_id).await
+}
+
+#[command]
+pub(crate) async fn destroy_session<R: Runtime>(
+    app: AppHandle<R>,
+    session_id: String,
+) -> Result<()> {
+    app.media().destroy_session(session_id).await
+}
+
 #[command]
 pub(crate) async fn initialize_session<R: Runtime>(
     app: AppHandle<R>,
+    webview: Webview<R>,
     request: InitializeMediaSessionRequest,
-) -> Result<()> {
-    app.media().initialize_session(request)
+    session_id: Option<String>,
+) -> Result<MediaSessionInfo> {
+    let session = session(&app, session_id);
+    let bind_to_window = request.bind_to_window;
+    let info = session.initialize_session(request).await?;
+    if bind_to_window {
+        session.bind_window(webview.window().label());
+    } else {
+        session.unbind_window();
+    }
+    Ok(info)
 }
 
 #[command]
 pub(crate) async fn set_metadata<R: Runtime>(
     app: AppHandle<R>,
     metadata: MediaMetadata,
+    session_id: Option<String>,
 ) -> Result<()> {
-    app.media().set_metadata(metadata)
+    session(&app, session_id).set_metadata(metadata).await
 }
 
 #[command]
 pub(crate) async fn set_playback_info<R: Runtime>(
     app: AppHandle<R>,
     info: PlaybackInfo,
+    session_id: Option<String>,
 ) -> Result<()> {
-    app.media().set_playback_info(info)
+    session(&app, session_id).set_playback_info(info).await
 }
 
 #[command]
 pub(crate) async fn set_playback_status<R: Runtime>(
     app: AppHandle<R>,
     status: PlaybackStatus,
+    session_id: Option<String>,
+) -> Result<()> {
+    session(&app, session_id).set_playback_status(status).await
+}
+
+#[command]
+pub(crate) async fn set_position<R: Runtime>(
+    app: AppHandle<R>,
+    position: f64,
+    session_id: Option<String>,
+) -> Result<()> {
+    session(&app, session_id).set_position(position).await
+}
+
+#[command]
+pub(crate) async fn clear_metadata<R: Runtime>(
+    app: AppHandle<R>,
+    session_id: Option<String>,
+) -> Result<()> {
+    session(&app, session_id).clear_metadata().await
+}
+
+#[command]
+pub(crate) async fn set_capabilities<R: Runtime>(
+    app: AppHandle<R>,
+    capabilities: MediaCapabilities,
+    session_id: Option<String>,
 ) -> Result<()> {
-    app.media().set_playback_status(status)
+    session(&app, session_id)
+        .set_capabilities(capabilities)
+        .await
 }
 
 #[command]
-pub(crate) async fn set_position<R: Runtime>(app: AppHandle<R>, position: f64) -> Result<()> {
-    app.media().set_position(position)
+pub(crate) async fn set_track_list<R: Runtime>(
+    app: AppHandle<R>,
+    tracks: Vec<Track>,
+    session_id: Option<String>,
+) -> Result<()> {
+    session(&app, session_id).set_track_list(tracks).await
 }
 
 #[command]
-pub(crate) async fn clear_metadata<R: Runtime>(app: AppHandle<R>) -> Result<()> {
-    app.media().clear_metadata()
+pub(crate) async fn add_track<R: Runtime>(
+    app: AppHandle<R>,
+    track: Track,
+    after_track_id: Option<String>,
+    session_id: Option<String>,
+) -> Result<()> {
+    session(&app, session_id)
+        .add_track(track, after_track_id)
+        .await
 }
 
 #[command]
-pub(crate) async fn get_metadata<R: Runtime>(app: AppHandle<R>) -> Result<Option<MediaMetadata>> {
-    app.media().get_metadata()
+pub(crate) async fn remove_track<R: Runtime>(
+    app: AppHandle<R>,
+    track_id: String,
+    session_id: Option<String>,
+) -> Result<()> {
+    session(&app, session_id).remove_track(track_id).await
+}
+
+#[command]
+pub(crate) async fn update_track_metadata<R: Runtime>(
+    app: AppHandle<R>,
+    track_id: String,
+    metadata: MediaMetadata,
+    session_id: Option<String>,
+) -> Result<()> {
+    session(&app, session_id)
+        .update_track_metadata(track_id, metadata)
+        .await
+}
+
+#[command]
+pub(crate) async fn set_playlists<R: Runtime>(
+    app: AppHandle<R>,
+    playlists: Vec<Playlist>,
+    active_playlist_id: Option<String>,
+    session_id: Option<String>,
+) -> Result<()> {
+    session(&app, session_id)
+        .set_playlists(playlists, active_playlist_id)
+        .await
+}
+
+#[command]
+pub(crate) async fn list_sessions<R: Runtime>(
+    app: AppHandle<R>,
+) -> Result<Vec<SystemMediaSession>> {
+    app.media().list_sessions().await
+}
+
+#[command]
+pub(crate) async fn control_session<R: Runtime>(
+    app: AppHandle<R>,
+    session_id: Option<String>,
+    action: SessionAction,
+) -> Result<()> {
+    app.media().control_session(session_id, action).await
+}
+
+#[command]
+pub(crate) async fn watch_sessions<R: Runtime>(app: AppHandle<R>) -> Result<()> {
+    app.media().watch_sessions().await
+}
+
+#[command]
+pub(crate) async fn unwatch_sessions<R: Runtime>(app: AppHandle<R>) -> Result<()> {
+    app.media().unwatch_sessions().await
+}
+
+#[command]
+pub(crate) async fn subscribe_events<R: Runtime>(
+    app: AppHandle<R>,
+    webview: Webview<R>,
+    channel: Channel<MediaControlEvent>,
+) -> Result<u32> {
+    Ok(app.media().subscribe_events(&webview, channel))
+}
+
+#[command]
+pub(crate) async fn unsubscribe<R: Runtime>(app: AppHandle<R>, id: u32) -> Result<()> {
+    app.media().unsubscribe(id);
+    Ok(())
+}
+
+#[command]
+pub(crate) async fn get_metadata<R: Runtime>(
+    app: AppHandle<R>,
+    session_id: Option<String>,
+) -> Result<Option<MediaMetadata>> {
+    session(&app, session_id).get_metadata().await
 }
 
 #[command]
 pub(crate) async fn get_playback_info<R: Runtime>(
     app: AppHandle<R>,
+    session_id: Option<String>,
 ) -> Result<Option<PlaybackInfo>> {
-    app.media().get_playback_info()
+    session(&app, session_id).get_playback_info().await
 }
 
 #[command]
-pub(crate) async fn get_playback_status<R: Runtime>(app: AppHandle<R>) -> Result<PlaybackStatus> {
-    app.media().get_playback_status()
+pub(crate) async fn get_playback_status<R: Runtime>(
+    app: AppHandle<R>,
+    session_id: Option<String>,
+) -> Result<PlaybackStatus> {
+    session(&app, session_id).get_playback_status().await
 }
 
 #[command]
-pub(crate) async fn get_position<R: Runtime>(app: AppHandle<R>) -> Result<f64> {
-    app.media().get_position()
+pub(crate) async fn get_position<R: Runtime>(
+    app: AppHandle<R>,
+    session_id: Option<String>,
+) -> Result<f64> {
+    session(&app, session_id).get_position().await
 }
 
 #[command]
-pub(crate) async fn is_enabled<R: Runtime>(app: AppHandle<R>) -> Result<bool> {
-    app.media().is_enabled()
+pub(crate) async fn get_system_metadata<R: Runtime>(
+    app: AppHandle<R>,
+) -> Result<Option<MediaMetadata>> {
+    app.media().get_system_metadata().await
 }
 
 #[command]
-pub(crate) async fn next<R: Runtime>(app: AppHandle<R>) -> Result<()> {
-    // Trigger next track event - implementation depends on event handler
-    Ok(())
+pub(crate) async fn get_system_playback_info<R: Runtime>(
+    app: AppHandle<R>,
+) -> Result<Option<PlaybackInfo>> {
+    app.media().get_system_playback_info().await
 }
 
 #[command]
-pub(crate) async fn previous<R: Runtime>(app: AppHandle<R>) -> Result<()> {
-    // Trigger previous track event - implementation depends on event handler
-    Ok(())
+pub(crate) async fn get_system_playback_status<R: Runtime>(
+    app: AppHandle<R>,
+) -> Result<PlaybackStatus> {
+    app.media().get_system_playback_status().await
+}
+
+#[command]
+pub(crate) async fn get_system_position<R: Runtime>(app: AppHandle<R>) -> Result<f64> {
+    app.media().get_system_position().await
+}
+
+#[command]
+pub(crate) async fn is_enabled<R: Runtime>(
+    app: AppHandle<R>,
+    session_id: Option<String>,
+) -> Result<bool> {
+    session(&app, session_id).is_enabled().await
+}
+
+#[command]
+pub(crate) async fn get_track_list<R: Runtime>(
+    app: AppHandle<R>,
+    session_id: Option<String>,
+) -> Result<Vec<Track>> {
+    session(&app, session_id).get_track_list().await
+}
+
+#[command]
+pub(crate) async fn next<R: Runtime>(app: AppHandle<R>, session_id: Option<String>) -> Result<()> {
+    session(&app, session_id).next().await
+}
+
+#[command]
+pub(crate) async fn previous<R: Runtime>(
+    app: AppHandle<R>,
+    session_id: Option<String>,
+) -> Result<()> {
+    session(&app, session_id).previous().await
 }
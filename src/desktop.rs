@@ -1,110 +1,951 @@
 use serde::de::DeserializeOwned;
-use std::sync::{Arc, Mutex};
-use tauri::{plugin::PluginApi, AppHandle, Runtime};
+use std::collections::HashMap;
+use std::panic::AssertUnwindSafe;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex, PoisonError};
+use tauri::{
+    ipc::Channel, plugin::PluginApi, AppHandle, Emitter, EventTarget, Manager, Runtime, Webview,
+};
+use tokio::sync::oneshot;
 
 use crate::models::*;
 use crate::platform;
 
+type EventHandler = Arc<Mutex<Option<Arc<dyn Fn(MediaControlEvent) + Send + Sync>>>>;
+
+/// A channel registered through `subscribe_events`, with the webview and
+/// window it came from so it can be dropped when they go away.
+struct Subscription {
+    webview: String,
+    window: String,
+    channel: Channel<MediaControlEvent>,
+}
+
+type Subscriptions = Arc<Mutex<HashMap<u32, Subscription>>>;
+
+/// Label of the window each bound session belongs to, keyed by session id.
+type Bindings = Arc<Mutex<HashMap<String, String>>>;
+
+type Dispatcher = Arc<dyn Fn(MediaControlEvent) + Send + Sync>;
+
+/// Work sent to the controller thread.
+type Job = Box<dyn FnOnce(&mut Sessions) + Send>;
+
+/// The controllers of the application's sessions, owned by the controller
+/// thread and keyed by session id.
+struct Sessions {
+    config: Config,
+    context: platform::PlatformContext,
+    artwork_dir: PathBuf,
+    dispatcher: Dispatcher,
+    controllers: HashMap<String, Box<dyn platform::MediaController + Send>>,
+    // `watch_sessions` calls not yet matched by `unwatch_sessions`. The
+    // watcher on the default session runs while this is non-zero
+    session_watchers: usize,
+}
+
+impl Sessions {
+    fn create(&mut self, id: &str) -> crate::Result<()> {
+        if id.is_empty() {
+            return Err(crate::Error::InvalidArgument {
+                field: "sessionId".to_string(),
+                reason: "must not be empty".to_string(),
+            });
+        }
+        if self.controllers.contains_key(id) {
+            return Err(crate::Error::InvalidArgument {
+                field: "sessionId".to_string(),
+                reason: format!("a session with the id {} already exists", id),
+            });
+        }
+        if id != DEFAULT_SESSION_ID && !platform::MULTIPLE_SESSIONS {
+            return Err(crate::Error::Unsupported {
+                feature: "multiple media sessions".to_string(),
+            });
+        }
+
+        let instance = (id != DEFAULT_SESSION_ID).then_some(id);
+        let mut controller = platform::create_media_controller(
+            &self.config,
+            &self.context,
+            self.artwork_dir.clone(),
+            instance,
+        );
+        let dispatcher = self.dispatcher.clone();
+        let session_id = id.to_string();
+        controller.set_event_handler(Box::new(move |mut event| {
+            event.session_id = Some(session_id.clone());
+            dispatcher(event)
+        }));
+        self.controllers.insert(id.to_string(), controller);
+        Ok(())
+    }
+
+    fn destroy(&mut self, id: &str) -> crate::Result<()> {
+        if id == DEFAULT_SESSION_ID {
+            return Err(crate::Error::InvalidArgument {
+                field: "sessionId".to_string(),
+                reason: "the default session cannot be destroyed".to_string(),
+            });
+        }
+        self.controllers
+            .remove(id)
+            .map(drop)
+            .ok_or_else(|| crate::Error::SessionNotFound(id.to_string()))
+    }
+
+    /// Withdraws everything a session published but keeps its controller,
+    /// so a session watcher running on it carries on.
+    fn end(&mut self, id: &str) -> crate::Result<()> {
+        self.get(id)?.end_session()
+    }
+
+    fn get(&mut self, id: &str) -> crate::Result<&mut dyn platform::MediaController> {
+        match self.controllers.get_mut(id) {
+            Some(controller) => Ok(controller.as_mut()),
+            None => Err(crate::Error::SessionNotFound(id.to_string())),
+        }
+    }
+}
+
+/// Tauri event carrying every incoming [`MediaControlEvent`].
+pub const CONTROL_EVENT: &str = "media://control";
+
 pub fn init<R: Runtime, C: DeserializeOwned>(
     app: &AppHandle<R>,
     _api: PluginApi<R, C>,
+    config: Config,
 ) -> crate::Result<Media<R>> {
-    let controller = platform::create_media_controller();
+    let artwork_dir = app
+        .path()
+        .app_cache_dir()
+        .unwrap_or_else(|_| std::env::temp_dir())
+        .join("media-artwork");
+    let event_handler: EventHandler = Arc::new(Mutex::new(None));
+    let emit_events = Arc::new(AtomicBool::new(true));
+    let subscriptions: Subscriptions = Arc::new(Mutex::new(HashMap::new()));
+    let bindings: Bindings = Arc::new(Mutex::new(HashMap::new()));
+
+    // Every event, from the controller or from our own commands, goes through
+    // this dispatcher, which handles the application-level requests before
+    // handing events to the user handler
+    let dispatcher: Dispatcher = Arc::new({
+        let app = app.clone();
+        let config = config.clone();
+        let event_handler = event_handler.clone();
+        let emit_events = emit_events.clone();
+        let subscriptions = subscriptions.clone();
+        let bindings = bindings.clone();
+        move |event: MediaControlEvent| {
+            // Next and Previous move through the track list before anyone
+            // sees them, whether they come from the system or our commands
+            let offset = match event.event_type {
+                MediaControlEventType::Next => Some(1),
+                MediaControlEventType::Previous => Some(-1),
+                _ => None,
+            };
+            if let (Some(offset), Some(media)) = (offset, app.try_state::<Media<R>>()) {
+                let id = event
+                    .session_id
+                    .clone()
+                    .unwrap_or_else(|| DEFAULT_SESSION_ID.to_string());
+                media.session(id).advance(offset);
+            }
+
+            // Events of a bound session only reach the webviews of its window
+            let window = event.session_id.as_ref().and_then(|id| {
+                bindings
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .get(id)
+                    .cloned()
+            });
+
+            if emit_events.load(Ordering::Relaxed) {
+                let result = match &window {
+                    Some(label) => {
+                        app.emit_to(EventTarget::window(label.as_str()), CONTROL_EVENT, &event)
+                    }
+                    None => app.emit(CONTROL_EVENT, &event),
+                };
+                if let Err(e) = result {
+                    log::warn!("Failed to emit media control event: {}", e);
+                }
+            }
+            // A failed send means the webview is gone, so its channel goes too
+            subscriptions
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .retain(|_, subscription| {
+                    if window
+                        .as_ref()
+                        .is_some_and(|label| &subscription.window != label)
+                    {
+                        return true;
+                    }
+                    subscription.channel.send(event.clone()).is_ok()
+                });
+
+            dispatch_event(&app, &config, &event_handler, event)
+        }
+    });
+
+    let (jobs, receiver) = mpsc::channel();
+    std::thread::Builder::new()
+        .name("media-controller".to_string())
+        .spawn({
+            let dispatcher = dispatcher.clone();
+            move || {
+                // Controllers are created here so that platform objects never
+                // change threads
+                let mut sessions = Sessions {
+                    context: platform::PlatformContext::new(&config),
+                    config,
+                    artwork_dir,
+                    dispatcher,
+                    controllers: HashMap::new(),
+                    session_watchers: 0,
+                };
+                if let Err(e) = sessions.create(DEFAULT_SESSION_ID) {
+                    log::error!("Failed to create the default media session: {}", e);
+                }
+                run_controller(&mut sessions, receiver);
+            }
+        })?;
+
     Ok(Media {
-        _app_handle: app.clone(),
-        controller: Arc::new(Mutex::new(controller)),
+        app_handle: app.clone(),
+        jobs,
+        dispatcher,
+        event_handler,
+        emit_events,
+        subscriptions,
+        next_subscription_id: AtomicU32::new(1),
+        bindings,
     })
 }
 
-/// Access to the media APIs.
+/// Runs jobs until [`Media`] is dropped. A panicking job fails only its
+/// caller.
+fn run_controller(sessions: &mut Sessions, jobs: mpsc::Receiver<Job>) {
+    for job in jobs {
+        if std::panic::catch_unwind(AssertUnwindSafe(|| job(&mut *sessions))).is_err() {
+            log::error!("A media controller call panicked");
+        }
+    }
+}
+
+fn dispatch_event<R: Runtime>(
+    app: &AppHandle<R>,
+    config: &Config,
+    event_handler: &EventHandler,
+    event: MediaControlEvent,
+) {
+    match event.event_type {
+        MediaControlEventType::Raise => {
+            raise_main_window(app);
+            return;
+        }
+        MediaControlEventType::Quit if config.quit_policy == QuitPolicy::Exit => {
+            app.exit(0);
+            return;
+        }
+        MediaControlEventType::Quit => {
+            if let Err(e) = app.emit("media://quit-requested", ()) {
+                log::warn!("Failed to emit quit request: {}", e);
+            }
+        }
+        _ => {}
+    }
+
+    // Called without the lock held so the handler may replace itself, and a
+    // panicking handler must not take down the thread that received the event
+    let handler = event_handler
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone();
+    if let Some(handler) = handler {
+        if std::panic::catch_unwind(AssertUnwindSafe(|| handler(event))).is_err() {
+            log::error!("The media event handler panicked");
+        }
+    }
+}
+
+/// Publishes the metadata of the track `offset` places away from the current
+/// one. Does nothing without a track list, past either end of it or before
+/// the session is initialized.
+fn advance_track_list(
+    controller: &mut dyn platform::MediaController,
+    offset: isize,
+) -> crate::Result<()> {
+    if !controller.is_enabled()? {
+        return Ok(());
+    }
+
+    let tracks = controller.get_track_list()?;
+    let current = controller
+        .get_metadata()?
+        .and_then(|metadata| metadata.track_id);
+
+    if let Some(track) = neighbour_track(&tracks, current.as_deref(), offset) {
+        let metadata = MediaMetadata {
+            track_id: Some(track.id.clone()),
+            ..track.metadata.clone()
+        };
+        controller.set_metadata(metadata)?;
+    }
+    Ok(())
+}
+
+/// The track `offset` places away from `current` in `tracks`. Without a
+/// current track, moving forward starts at the first one. Returns `None` past
+/// either end of the list.
+fn neighbour_track<'a>(
+    tracks: &'a [Track],
+    current: Option<&str>,
+    offset: isize,
+) -> Option<&'a Track> {
+    let index = match current.and_then(|id| tracks.iter().position(|track| track.id == id)) {
+        Some(index) => index.checked_add_signed(offset)?,
+        None if offset > 0 => 0,
+        None => return None,
+    };
+    tracks.get(index)
+}
+
+/// Unminimizes, shows and focuses the `main` window, or the first open
+/// window when the application has none with that label.
+fn raise_main_window<R: Runtime>(app: &AppHandle<R>) {
+    let window = app
+        .get_webview_window("main")
+        .or_else(|| app.webview_windows().into_values().next());
+
+    if let Some(window) = window {
+        let result = window
+            .unminimize()
+            .and_then(|_| window.show())
+            .and_then(|_| window.set_focus());
+        if let Err(e) = result {
+            log::warn!("Failed to raise window: {}", e);
+        }
+    }
+}
+
+/// Access to the media APIs. Methods that act on a session use the default
+/// one; see [`Media::session`] for the others.
 pub struct Media<R: Runtime> {
-    _app_handle: AppHandle<R>,
-    controller: Arc<Mutex<Box<dyn platform::MediaController + Send>>>,
+    app_handle: AppHandle<R>,
+    jobs: mpsc::Sender<Job>,
+    dispatcher: Dispatcher,
+    event_handler: EventHandler,
+    emit_events: Arc<AtomicBool>,
+    subscriptions: Subscriptions,
+    next_subscription_id: AtomicU32,
+    bindings: Bindings,
 }
 
 impl<R: Runtime> Media<R> {
-    pub fn initialize_session(&self, request: InitializeMediaSessionRequest) -> crate::Result<()> {
-        let mut controller = self.controller.lock().unwrap();
-        controller
-            .initialize_session(request.app_id, request.app_name)
-            .map_err(|e| crate::Error::String(e.to_string()))
+    /// Runs `f` on the controller thread and waits for its result without
+    /// blocking the async runtime.
+    async fn call<T, F>(&self, f: F) -> crate::Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut Sessions) -> crate::Result<T> + Send + 'static,
+    {
+        let (reply, result) = oneshot::channel();
+        self.jobs
+            .send(Box::new(move |sessions| {
+                let _ = reply.send(f(sessions));
+            }))
+            .map_err(|_| crate::Error::platform("the media controller has stopped"))?;
+        result
+            .await
+            .map_err(|_| crate::Error::platform("the media controller call failed"))?
+    }
+
+    /// Handle to the session with the given id. Calls through it fail with
+    /// [`crate::Error::SessionNotFound`] if the session does not exist.
+    pub fn session(&self, id: impl Into<String>) -> MediaSession<'_, R> {
+        MediaSession {
+            media: self,
+            id: id.into(),
+        }
+    }
+
+    fn default_session(&self) -> MediaSession<'_, R> {
+        self.session(DEFAULT_SESSION_ID)
+    }
+
+    /// Creates an additional session alongside the default one. Only
+    /// supported where [`platform::MULTIPLE_SESSIONS`] is set.
+    pub async fn create_session(&self, id: impl Into<String>) -> crate::Result<()> {
+        let id = id.into();
+        self.call(move |sessions| sessions.create(&id)).await
     }
 
-    pub fn set_metadata(&self, metadata: MediaMetadata) -> crate::Result<()> {
-        let mut controller = self.controller.lock().unwrap();
-        controller
-            .set_metadata(metadata)
-            .map_err(|e| crate::Error::String(e.to_string()))
+    /// Removes a session created with [`Media::create_session`].
+    pub async fn destroy_session(&self, id: impl Into<String>) -> crate::Result<()> {
+        let id = id.into();
+        self.bindings
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(&id);
+        self.call(move |sessions| sessions.destroy(&id)).await
+    }
+
+    /// Resets every session bound to a destroyed window: its status becomes
+    /// `Stopped`, its metadata is cleared and its bus name is released. The
+    /// session id stays valid and can be initialized again.
+    pub(crate) fn release_window(&self, label: &str) {
+        let mut released = Vec::new();
+        self.bindings
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .retain(|id, window| {
+                if window != label {
+                    return true;
+                }
+                released.push(id.clone());
+                false
+            });
+
+        for id in released {
+            // Not awaited, the window is already gone
+            let job: Job = Box::new(move |sessions| {
+                if let Err(e) = sessions.end(&id) {
+                    log::warn!("Failed to release media session {}: {}", id, e);
+                }
+            });
+            if self.jobs.send(job).is_err() {
+                break;
+            }
+        }
     }
 
-    pub fn set_playback_info(&self, info: PlaybackInfo) -> crate::Result<()> {
-        let mut controller = self.controller.lock().unwrap();
-        controller
-            .set_playback_info(info)
-            .map_err(|e| crate::Error::String(e.to_string()))
+    pub async fn initialize_session(
+        &self,
+        request: InitializeMediaSessionRequest,
+    ) -> crate::Result<MediaSessionInfo> {
+        self.default_session().initialize_session(request).await
     }
 
-    pub fn set_playback_status(&self, status: PlaybackStatus) -> crate::Result<()> {
-        let mut controller = self.controller.lock().unwrap();
-        controller
-            .set_playback_status(status)
-            .map_err(|e| crate::Error::String(e.to_string()))
+    pub async fn set_metadata(&self, metadata: MediaMetadata) -> crate::Result<()> {
+        self.default_session().set_metadata(metadata).await
     }
 
-    pub fn set_position(&self, position: f64) -> crate::Result<()> {
-        let mut controller = self.controller.lock().unwrap();
-        controller
-            .set_position(position)
-            .map_err(|e| crate::Error::String(e.to_string()))
+    pub async fn set_playback_info(&self, info: PlaybackInfo) -> crate::Result<()> {
+        self.default_session().set_playback_info(info).await
     }
 
-    pub fn clear_metadata(&self) -> crate::Result<()> {
-        let mut controller = self.controller.lock().unwrap();
-        controller
-            .clear_metadata()
-            .map_err(|e| crate::Error::String(e.to_string()))
+    pub async fn set_playback_status(&self, status: PlaybackStatus) -> crate::Result<()> {
+        self.default_session().set_playback_status(status).await
     }
 
+    pub async fn set_position(&self, position: f64) -> crate::Result<()> {
+        self.default_session().set_position(position).await
+    }
+
+    pub async fn clear_metadata(&self) -> crate::Result<()> {
+        self.default_session().clear_metadata().await
+    }
+
+    /// Calls `handler` for every incoming event. It runs on whichever thread
+    /// received the event, never the controller thread, so it may make
+    /// [`Media`] calls, best spawned onto the async runtime. A panicking
+    /// handler is logged and keeps receiving later events.
     pub fn set_event_handler<F>(&self, handler: F)
     where
-        F: Fn(MediaControlEvent) + Send + 'static,
+        F: Fn(MediaControlEvent) + Send + Sync + 'static,
     {
-        let mut controller = self.controller.lock().unwrap();
-        controller.set_event_handler(Box::new(handler));
+        *self
+            .event_handler
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = Some(Arc::new(handler));
+    }
+
+    /// Turns emitting incoming events as [`CONTROL_EVENT`] on or off. It is
+    /// on by default and independent of [`Media::set_event_handler`].
+    pub fn set_emit_events(&self, emit: bool) {
+        self.emit_events.store(emit, Ordering::Relaxed);
+    }
+
+    /// Streams every incoming event to `channel` until [`Media::unsubscribe`]
+    /// is called or `webview` reloads or closes. Returns the subscription id.
+    pub fn subscribe_events(
+        &self,
+        webview: &Webview<R>,
+        channel: Channel<MediaControlEvent>,
+    ) -> u32 {
+        let id = self.next_subscription_id.fetch_add(1, Ordering::Relaxed);
+        let subscription = Subscription {
+            webview: webview.label().to_string(),
+            window: webview.window().label().to_string(),
+            channel,
+        };
+        self.subscriptions
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(id, subscription);
+        id
+    }
+
+    /// Removes a subscription. Unknown ids are ignored.
+    pub fn unsubscribe(&self, id: u32) {
+        self.subscriptions
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(&id);
+    }
+
+    /// Drops the subscriptions of a webview whose page is being replaced.
+    pub(crate) fn unsubscribe_webview(&self, label: &str) {
+        self.subscriptions
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .retain(|_, subscription| subscription.webview != label);
+    }
+
+    /// Drops the subscriptions of every webview in a destroyed window.
+    pub(crate) fn unsubscribe_window(&self, label: &str) {
+        self.subscriptions
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .retain(|_, subscription| subscription.window != label);
+    }
+
+    /// Delivers a `Next` event as if it came from the system. Like every
+    /// `Next` event, it first moves to the next track of the track list, if
+    /// one is set.
+    pub async fn next(&self) -> crate::Result<()> {
+        self.default_session().next().await
+    }
+
+    /// Delivers a `Previous` event as if it came from the system, moving to
+    /// the previous track of the track list first, like [`Media::next`].
+    pub async fn previous(&self) -> crate::Result<()> {
+        self.default_session().previous().await
+    }
+
+    pub async fn set_capabilities(&self, capabilities: MediaCapabilities) -> crate::Result<()> {
+        self.default_session().set_capabilities(capabilities).await
+    }
+
+    pub async fn set_track_list(&self, tracks: Vec<Track>) -> crate::Result<()> {
+        self.default_session().set_track_list(tracks).await
+    }
+
+    pub async fn add_track(
+        &self,
+        track: Track,
+        after_track_id: Option<String>,
+    ) -> crate::Result<()> {
+        self.default_session()
+            .add_track(track, after_track_id)
+            .await
+    }
+
+    pub async fn remove_track(&self, track_id: String) -> crate::Result<()> {
+        self.default_session().remove_track(track_id).await
+    }
+
+    pub async fn update_track_metadata(
+        &self,
+        track_id: String,
+        metadata: MediaMetadata,
+    ) -> crate::Result<()> {
+        self.default_session()
+            .update_track_metadata(track_id, metadata)
+            .await
+    }
+
+    pub async fn set_playlists(
+        &self,
+        playlists: Vec<Playlist>,
+        active_playlist_id: Option<String>,
+    ) -> crate::Result<()> {
+        self.default_session()
+            .set_playlists(playlists, active_playlist_id)
+            .await
+    }
+
+    pub async fn list_sessions(&self) -> crate::Result<Vec<SystemMediaSession>> {
+        self.default_session()
+            .call(move |controller| controller.list_sessions())
+            .await
+    }
+
+    pub async fn control_session(
+        &self,
+        session_id: Option<String>,
+        action: SessionAction,
+    ) -> crate::Result<()> {
+        self.default_session()
+            .call(move |controller| controller.control_session(session_id, action))
+            .await
     }
 
-    pub fn get_metadata(&self) -> crate::Result<Option<MediaMetadata>> {
-        let controller = self.controller.lock().unwrap();
-        controller
-            .get_metadata()
-            .map_err(|e| crate::Error::String(e.to_string()))
+    /// Starts emitting `media://session-added`, `media://session-removed` and
+    /// `media://session-changed` as other media sessions come, go and change.
+    /// Calls are counted, so each caller stops watching with its own
+    /// [`Media::unwatch_sessions`] without affecting the others.
+    pub async fn watch_sessions(&self) -> crate::Result<()> {
+        let app = self.app_handle.clone();
+        let handler = Box::new(move |event| {
+            let result = match event {
+                SessionEvent::Added(session) => app.emit("media://session-added", session),
+                SessionEvent::Removed(id) => app.emit(
+                    "media://session-removed",
+                    SessionChange {
+                        id,
+                        ..Default::default()
+                    },
+                ),
+                SessionEvent::Changed(change) => app.emit("media://session-changed", change),
+            };
+            if let Err(e) = result {
+                log::warn!("Failed to emit session event: {}", e);
+            }
+        });
+        self.call(move |sessions| {
+            if sessions.session_watchers == 0 {
+                sessions.get(DEFAULT_SESSION_ID)?.watch_sessions(handler)?;
+            }
+            sessions.session_watchers += 1;
+            Ok(())
+        })
+        .await
     }
 
-    pub fn get_playback_info(&self) -> crate::Result<Option<PlaybackInfo>> {
-        let controller = self.controller.lock().unwrap();
-        controller
-            .get_playback_info()
-            .map_err(|e| crate::Error::String(e.to_string()))
+    /// Undoes one [`Media::watch_sessions`] call. The session events stop
+    /// once every call has been undone.
+    pub async fn unwatch_sessions(&self) -> crate::Result<()> {
+        self.call(|sessions| {
+            if sessions.session_watchers == 0 {
+                return Ok(());
+            }
+            sessions.session_watchers -= 1;
+            if sessions.session_watchers == 0 {
+                sessions.get(DEFAULT_SESSION_ID)?.unwatch_sessions();
+            }
+            Ok(())
+        })
+        .await
     }
 
-    pub fn get_playback_status(&self) -> crate::Result<PlaybackStatus> {
-        let controller = self.controller.lock().unwrap();
-        controller
-            .get_playback_status()
-            .map_err(|e| crate::Error::String(e.to_string()))
+    pub async fn get_metadata(&self) -> crate::Result<Option<MediaMetadata>> {
+        self.default_session().get_metadata().await
     }
 
-    pub fn get_position(&self) -> crate::Result<f64> {
-        let controller = self.controller.lock().unwrap();
-        controller
-            .get_position()
-            .map_err(|e| crate::Error::String(e.to_string()))
+    pub async fn get_playback_info(&self) -> crate::Result<Option<PlaybackInfo>> {
+        self.default_session().get_playback_info().await
     }
 
-    pub fn is_enabled(&self) -> crate::Result<bool> {
-        let controller = self.controller.lock().unwrap();
-        controller
-            .is_enabled()
-            .map_err(|e| crate::Error::String(e.to_string()))
+    pub async fn get_playback_status(&self) -> crate::Result<PlaybackStatus> {
+        self.default_session().get_playback_status().await
+    }
+
+    pub async fn get_position(&self) -> crate::Result<f64> {
+        self.default_session().get_position().await
+    }
+
+    pub async fn get_system_metadata(&self) -> crate::Result<Option<MediaMetadata>> {
+        self.default_session()
+            .call(move |controller| controller.get_system_metadata())
+            .await
+    }
+
+    pub async fn get_system_playback_info(&self) -> crate::Result<Option<PlaybackInfo>> {
+        self.default_session()
+            .call(move |controller| controller.get_system_playback_info())
+            .await
+    }
+
+    pub async fn get_system_playback_status(&self) -> crate::Result<PlaybackStatus> {
+        self.default_session()
+            .call(move |controller| controller.get_system_playback_status())
+            .await
+    }
+
+    pub async fn get_system_position(&self) -> crate::Result<f64> {
+        self.default_session()
+            .call(move |controller| controller.get_system_position())
+            .await
+    }
+
+    pub async fn is_enabled(&self) -> crate::Result<bool> {
+        self.default_session().is_enabled().await
+    }
+
+    pub async fn get_track_list(&self) -> crate::Result<Vec<Track>> {
+        self.default_session().get_track_list().await
+    }
+}
+
+/// One of the application's media sessions, obtained from [`Media::session`].
+pub struct MediaSession<'a, R: Runtime> {
+    media: &'a Media<R>,
+    id: String,
+}
+
+impl<R: Runtime> MediaSession<'_, R> {
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Ties the session to the window labeled `window`. Its control events
+    /// then reach only that window's webviews, and destroying the window
+    /// stops the session, clears its metadata and releases its bus name.
+    pub fn bind_window(&self, window: impl Into<String>) {
+        self.media
+            .bindings
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(self.id.clone(), window.into());
+    }
+
+    /// Undoes [`MediaSession::bind_window`].
+    pub fn unbind_window(&self) {
+        self.media
+            .bindings
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(&self.id);
+    }
+
+    /// Runs `f` on this session's controller.
+    async fn call<T, F>(&self, f: F) -> crate::Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut dyn platform::MediaController) -> crate::Result<T> + Send + 'static,
+    {
+        let id = self.id.clone();
+        self.media.call(move |sessions| f(sessions.get(&id)?)).await
+    }
+
+    /// Like [`MediaSession::call`], for calls that publish to the system,
+    /// which fail with [`crate::Error::NotInitialized`] until
+    /// [`MediaSession::initialize_session`] has run.
+    async fn publish<F>(&self, f: F) -> crate::Result<()>
+    where
+        F: FnOnce(&mut dyn platform::MediaController) -> crate::Result<()> + Send + 'static,
+    {
+        self.call(move |controller| {
+            if !controller.is_enabled()? {
+                return Err(crate::Error::NotInitialized);
+            }
+            f(controller)
+        })
+        .await
+    }
+
+    pub async fn initialize_session(
+        &self,
+        request: InitializeMediaSessionRequest,
+    ) -> crate::Result<MediaSessionInfo> {
+        self.call(move |controller| controller.initialize_session(request))
+            .await
+    }
+
+    pub async fn set_metadata(&self, metadata: MediaMetadata) -> crate::Result<()> {
+        self.publish(move |controller| controller.set_metadata(metadata))
+            .await
+    }
+
+    pub async fn set_playback_info(&self, info: PlaybackInfo) -> crate::Result<()> {
+        self.publish(move |controller| controller.set_playback_info(info))
+            .await
+    }
+
+    pub async fn set_playback_status(&self, status: PlaybackStatus) -> crate::Result<()> {
+        self.publish(move |controller| controller.set_playback_status(status))
+            .await
+    }
+
+    pub async fn set_position(&self, position: f64) -> crate::Result<()> {
+        if !position.is_finite() || position < 0.0 {
+            return Err(crate::Error::InvalidArgument {
+                field: "position".to_string(),
+                reason: "must be a non-negative number of seconds".to_string(),
+            });
+        }
+        self.publish(move |controller| controller.set_position(position))
+            .await
+    }
+
+    pub async fn clear_metadata(&self) -> crate::Result<()> {
+        self.publish(move |controller| controller.clear_metadata())
+            .await
+    }
+
+    /// Delivers a `Next` event for this session as if it came from the
+    /// system. Like every `Next` event, it first moves to the next track of
+    /// the track list, if one is set.
+    pub async fn next(&self) -> crate::Result<()> {
+        self.skip(MediaControlEventType::Next).await
+    }
+
+    /// Delivers a `Previous` event for this session as if it came from the
+    /// system, moving to the previous track of the track list first, like
+    /// [`MediaSession::next`].
+    pub async fn previous(&self) -> crate::Result<()> {
+        self.skip(MediaControlEventType::Previous).await
+    }
+
+    async fn skip(&self, event_type: MediaControlEventType) -> crate::Result<()> {
+        // Fails like the publishing calls for unknown or uninitialized sessions
+        self.publish(|_| Ok(())).await?;
+
+        // Dispatched from here rather than the controller thread, since
+        // handlers may call back in
+        let mut event = MediaControlEvent::new(event_type);
+        event.session_id = Some(self.id.clone());
+        (self.media.dispatcher)(event);
+        Ok(())
+    }
+
+    /// Queues the move through the track list for a `Next` or `Previous`
+    /// event without waiting for it. Jobs run in order, so any call made
+    /// after this one, including from the event handlers, sees the new track.
+    fn advance(&self, offset: isize) {
+        let id = self.id.clone();
+        let job: Job = Box::new(move |sessions| {
+            let result = sessions
+                .get(&id)
+                .and_then(|controller| advance_track_list(controller, offset));
+            if let Err(e) = result {
+                log::warn!("Failed to advance the track list of session {}: {}", id, e);
+            }
+        });
+        let _ = self.media.jobs.send(job);
+    }
+
+    pub async fn set_capabilities(&self, capabilities: MediaCapabilities) -> crate::Result<()> {
+        self.call(move |controller| controller.set_capabilities(capabilities))
+            .await
+    }
+
+    pub async fn set_track_list(&self, tracks: Vec<Track>) -> crate::Result<()> {
+        self.publish(move |controller| controller.set_track_list(tracks))
+            .await
+    }
+
+    pub async fn add_track(
+        &self,
+        track: Track,
+        after_track_id: Option<String>,
+    ) -> crate::Result<()> {
+        self.publish(move |controller| controller.add_track(track, after_track_id))
+            .await
+    }
+
+    pub async fn remove_track(&self, track_id: String) -> crate::Result<()> {
+        self.publish(move |controller| controller.remove_track(track_id))
+            .await
+    }
+
+    pub async fn update_track_metadata(
+        &self,
+        track_id: String,
+        metadata: MediaMetadata,
+    ) -> crate::Result<()> {
+        self.publish(move |controller| controller.update_track_metadata(track_id, metadata))
+            .await
+    }
+
+    pub async fn set_playlists(
+        &self,
+        playlists: Vec<Playlist>,
+        active_playlist_id: Option<String>,
+    ) -> crate::Result<()> {
+        if let Some(id) = &active_playlist_id {
+            if !playlists.iter().any(|playlist| &playlist.id == id) {
+                return Err(crate::Error::InvalidArgument {
+                    field: "activePlaylistId".to_string(),
+                    reason: format!("no playlist has the id {}", id),
+                });
+            }
+        }
+        self.publish(move |controller| controller.set_playlists(playlists, active_playlist_id))
+            .await
+    }
+
+    pub async fn get_metadata(&self) -> crate::Result<Option<MediaMetadata>> {
+        self.call(move |controller| controller.get_metadata()).await
+    }
+
+    pub async fn get_playback_info(&self) -> crate::Result<Option<PlaybackInfo>> {
+        self.call(move |controller| controller.get_playback_info())
+            .await
+    }
+
+    pub async fn get_playback_status(&self) -> crate::Result<PlaybackStatus> {
+        self.call(move |controller| controller.get_playback_status())
+            .await
+    }
+
+    pub async fn get_position(&self) -> crate::Result<f64> {
+        self.call(move |controller| controller.get_position()).await
+    }
+
+    pub async fn is_enabled(&self) -> crate::Result<bool> {
+        self.call(move |controller| controller.is_enabled()).await
+    }
+
+    pub async fn get_track_list(&self) -> crate::Result<Vec<Track>> {
+        self.call(move |controller| controller.get_track_list())
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tracks(ids: &[&str]) -> Vec<Track> {
+        ids.iter()
+            .map(|id| Track {
+                id: id.to_string(),
+                metadata: MediaMetadata::default(),
+            })
+            .collect()
+    }
+
+    fn neighbour<'a>(tracks: &'a [Track], current: Option<&str>, offset: isize) -> Option<&'a str> {
+        neighbour_track(tracks, current, offset).map(|track| track.id.as_str())
+    }
+
+    #[test]
+    fn moves_to_adjacent_tracks() {
+        let tracks = tracks(&["a", "b", "c"]);
+        assert_eq!(neighbour(&tracks, Some("b"), 1), Some("c"));
+        assert_eq!(neighbour(&tracks, Some("b"), -1), Some("a"));
+    }
+
+    #[test]
+    fn stops_at_the_ends_of_the_list() {
+        let tracks = tracks(&["a", "b", "c"]);
+        assert_eq!(neighbour(&tracks, Some("c"), 1), None);
+        assert_eq!(neighbour(&tracks, Some("a"), -1), None);
+    }
+
+    #[test]
+    fn starts_at_the_first_track_without_a_current_one() {
+        let tracks = tracks(&["a", "b", "c"]);
+        assert_eq!(neighbour(&tracks, None, 1), Some("a"));
+        assert_eq!(neighbour(&tracks, None, -1), None);
+        assert_eq!(neighbour(&tracks, Some("unknown"), 1), Some("a"));
+        assert_eq!(neighbour(&tracks, Some("unknown"), -1), None);
+    }
+
+    #[test]
+    fn empty_and_single_track_lists() {
+        assert_eq!(neighbour(&[], None, 1), None);
+        assert_eq!(neighbour(&[], Some("a"), -1), None);
+
+        let tracks = tracks(&["a"]);
+        assert_eq!(neighbour(&tracks, Some("a"), 1), None);
+        assert_eq!(neighbour(&tracks, Some("a"), -1), None);
     }
 }
@@ -2,19 +2,111 @@ use serde::{ser::Serializer, Serialize};
 
 pub type Result<T> = std::result::Result<T, Error>;
 
+/// Underlying cause of a failure reported by the operating system's media APIs.
+pub type PlatformError = Box<dyn std::error::Error + Send + Sync>;
+
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
-    #[error(transparent)]
-    Io(#[from] std::io::Error),
-    #[error("{0}")]
-    String(String),
+    #[error("the media session has not been initialized")]
+    NotInitialized,
+    #[error("the session bus is unavailable: {source}")]
+    BusUnavailable { source: PlatformError },
+    #[error("the bus name {name} is already taken")]
+    NameTaken { name: String },
+    #[error("invalid argument `{field}`: {reason}")]
+    InvalidArgument { field: String, reason: String },
+    #[error("not supported: {feature}")]
+    Unsupported { feature: String },
+    #[error("media session not found: {0}")]
+    SessionNotFound(String),
+    #[error("there is no active media session")]
+    NoActiveSession,
+    #[error("platform error: {source}")]
+    Platform { source: PlatformError },
+}
+
+impl Error {
+    /// Wraps a failure of the platform's media APIs.
+    pub fn platform(source: impl Into<PlatformError>) -> Self {
+        Error::Platform {
+            source: source.into(),
+        }
+    }
+
+    /// Stable identifier of the variant, sent to the frontend as `code`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Error::NotInitialized => "not_initialized",
+            Error::BusUnavailable { .. } => "bus_unavailable",
+            Error::NameTaken { .. } => "name_taken",
+            Error::InvalidArgument { .. } => "invalid_argument",
+            Error::Unsupported { .. } => "unsupported",
+            Error::SessionNotFound(_) => "session_not_found",
+            Error::NoActiveSession => "no_active_session",
+            Error::Platform { .. } => "platform",
+        }
+    }
+
+    fn details(&self) -> Option<ErrorDetails<'_>> {
+        match self {
+            Error::NameTaken { name } => Some(ErrorDetails::NameTaken { name }),
+            Error::InvalidArgument { field, reason } => {
+                Some(ErrorDetails::InvalidArgument { field, reason })
+            }
+            Error::Unsupported { feature } => Some(ErrorDetails::Unsupported { feature }),
+            Error::SessionNotFound(session_id) => {
+                Some(ErrorDetails::SessionNotFound { session_id })
+            }
+            Error::NotInitialized
+            | Error::BusUnavailable { .. }
+            | Error::NoActiveSession
+            | Error::Platform { .. } => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(error: std::io::Error) -> Self {
+        Error::platform(error)
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl From<dbus::Error> for Error {
+    fn from(error: dbus::Error) -> Self {
+        Error::platform(error)
+    }
+}
+
+#[cfg(target_os = "windows")]
+impl From<windows::core::Error> for Error {
+    fn from(error: windows::core::Error) -> Self {
+        Error::platform(error)
+    }
 }
 
+/// Variant-specific fields sent to the frontend as `details`.
+#[derive(Serialize)]
+#[serde(untagged, rename_all_fields = "camelCase")]
+enum ErrorDetails<'a> {
+    NameTaken { name: &'a str },
+    InvalidArgument { field: &'a str, reason: &'a str },
+    Unsupported { feature: &'a str },
+    SessionNotFound { session_id: &'a str },
+}
+
+/// Errors cross the IPC boundary as `{ code, message, details }`.
 impl Serialize for Error {
     fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
-        serializer.serialize_str(self.to_string().as_ref())
+        use serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("Error", 3)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.serialize_field("details", &self.details())?;
+        state.end()
     }
 }
@@ -1,6 +1,7 @@
+use tauri::webview::PageLoadEvent;
 use tauri::{
     plugin::{Builder, TauriPlugin},
-    Manager, Runtime,
+    Manager, RunEvent, Runtime, WindowEvent,
 };
 
 pub use models::*;
@@ -13,10 +14,14 @@ mod error;
 mod models;
 pub mod platform;
 
-pub use error::{Error, Result};
+pub use error::{Error, PlatformError, Result};
 
 #[cfg(desktop)]
 use desktop::Media;
+#[cfg(desktop)]
+pub use desktop::MediaSession;
+#[cfg(desktop)]
+pub use desktop::CONTROL_EVENT;
 
 /// Extensions to [`tauri::App`], [`tauri::AppHandle`] and [`tauri::Window`] to access the media APIs.
 pub trait MediaExt<R: Runtime> {
@@ -29,29 +34,79 @@ impl<R: Runtime, T: Manager<R>> crate::MediaExt<R> for T {
     }
 }
 
-/// Initializes the plugin.
-pub fn init<R: Runtime>() -> TauriPlugin<R> {
-    Builder::new("media")
+/// Initializes the plugin with the configuration from `plugins.media` in
+/// `tauri.conf.json`, falling back to the defaults when it is absent.
+pub fn init<R: Runtime>() -> TauriPlugin<R, Option<Config>> {
+    build(None)
+}
+
+/// Initializes the plugin with an explicit configuration, ignoring
+/// `plugins.media` in `tauri.conf.json`.
+pub fn init_with_config<R: Runtime>(config: Config) -> TauriPlugin<R, Option<Config>> {
+    build(Some(config))
+}
+
+fn build<R: Runtime>(config: Option<Config>) -> TauriPlugin<R, Option<Config>> {
+    Builder::<R, Option<Config>>::new("media")
         .invoke_handler(tauri::generate_handler![
+            commands::create_session,
+            commands::destroy_session,
             commands::initialize_session,
             commands::set_metadata,
             commands::set_playback_info,
             commands::set_playback_status,
             commands::set_position,
             commands::clear_metadata,
+            commands::set_capabilities,
+            commands::set_track_list,
+            commands::add_track,
+            commands::remove_track,
+            commands::update_track_metadata,
+            commands::set_playlists,
             commands::get_metadata,
             commands::get_playback_info,
             commands::get_playback_status,
             commands::get_position,
+            commands::get_system_metadata,
+            commands::get_system_playback_info,
+            commands::get_system_playback_status,
+            commands::get_system_position,
             commands::is_enabled,
+            commands::get_track_list,
+            commands::list_sessions,
+            commands::control_session,
+            commands::watch_sessions,
+            commands::unwatch_sessions,
+            commands::subscribe_events,
+            commands::unsubscribe,
             commands::next,
             commands::previous,
         ])
         .setup(|app, api| {
+            let config = config.or_else(|| api.config().clone()).unwrap_or_default();
             #[cfg(desktop)]
-            let media = desktop::init(app, api)?;
+            let media = desktop::init(app, api, config)?;
             app.manage(media);
             Ok(())
         })
+        .on_page_load(|webview, payload| {
+            // The new page cannot receive on the previous page's channels, so drop them
+            #[cfg(desktop)]
+            if payload.event() == PageLoadEvent::Started {
+                webview.media().unsubscribe_webview(webview.label());
+            }
+        })
+        .on_event(|app, event| {
+            #[cfg(desktop)]
+            if let RunEvent::WindowEvent {
+                label,
+                event: WindowEvent::Destroyed,
+                ..
+            } = event
+            {
+                app.media().unsubscribe_window(label);
+                app.media().release_window(label);
+            }
+        })
         .build()
 }
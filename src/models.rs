@@ -1,7 +1,7 @@
 use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct MediaMetadata {
     pub title: String,
@@ -10,8 +10,58 @@ pub struct MediaMetadata {
     pub album_artist: Option<String>,
     pub duration: Option<f64>, // Duration in seconds
     pub artwork_url: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none", with = "base64_serde")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "base64_serde"
+    )]
     pub artwork_data: Option<Vec<u8>>, // Raw image data
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub track_id: Option<String>, // Id of this track in the published track list
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub artists: Vec<String>, // All artists, takes precedence over `artist`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub album_artists: Vec<String>, // All album artists, takes precedence over `album_artist`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub genres: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub track_number: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub disc_number: Option<u32>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub composers: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub lyricists: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub release_date: Option<String>, // ISO 8601 date or date-time
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>, // Location of the media itself
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_rating: Option<f64>, // From 0.0 to 1.0
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub play_count: Option<u32>,
+}
+
+impl MediaMetadata {
+    /// Every artist, falling back to the single `artist` field.
+    pub fn all_artists(&self) -> Vec<String> {
+        if self.artists.is_empty() {
+            self.artist.iter().cloned().collect()
+        } else {
+            self.artists.clone()
+        }
+    }
+
+    /// Every album artist, falling back to the single `album_artist` field.
+    pub fn all_album_artists(&self) -> Vec<String> {
+        if self.album_artists.is_empty() {
+            self.album_artist.iter().cloned().collect()
+        } else {
+            self.album_artists.clone()
+        }
+    }
 }
 
 mod base64_serde {
@@ -62,6 +112,12 @@ pub struct PlaybackInfo {
     pub shuffle: bool,
     pub repeat_mode: RepeatMode,
     pub playback_rate: f64,
+    #[serde(default = "default_volume")]
+    pub volume: f64, // Volume from 0.0 to 1.0
+}
+
+fn default_volume() -> f64 {
+    1.0
 }
 
 #[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq)]
@@ -75,12 +131,32 @@ pub enum RepeatMode {
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct MediaControlEvent {
-    pub event_type: MediaControlEventType,
+    #[serde(flatten)]
+    pub event_type: MediaControlEventType, // Serialized as `eventType` plus `data` for variants that carry one
     pub timestamp: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>, // Session the event is addressed to
 }
 
-#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
-#[serde(rename_all = "camelCase")]
+/// Id of the session every application has, used when no session id is given.
+pub const DEFAULT_SESSION_ID: &str = "default";
+
+impl MediaControlEvent {
+    /// Creates an event of the given type stamped with the current time.
+    pub fn new(event_type: MediaControlEventType) -> Self {
+        MediaControlEvent {
+            event_type,
+            timestamp: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default(),
+            session_id: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", tag = "eventType", content = "data")]
 pub enum MediaControlEventType {
     Play,
     Pause,
@@ -91,8 +167,65 @@ pub enum MediaControlEventType {
     FastForward,
     Rewind,
     SeekTo(f64),
+    SeekBy(f64), // Relative offset in seconds, negative seeks backwards
     SetPosition(f64),
     SetPlaybackRate(f64),
+    SetShuffle(bool),
+    SetRepeatMode(RepeatMode),
+    SetVolume(f64),
+    GoTo(String), // Track id to jump to
+    #[serde(rename_all = "camelCase")]
+    AddTrack {
+        uri: String,
+        after_track_id: Option<String>, // None inserts at the start of the list
+        set_as_current: bool,
+    },
+    RemoveTrack(String),      // Track id to remove
+    ActivatePlaylist(String), // Playlist id to switch to
+    Raise,
+    Quit,
+    OpenUri(String), // URI a desktop client asked the application to open
+}
+
+/// Which actions the session currently offers to the system media UI.
+/// Missing fields default to `true`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct MediaCapabilities {
+    pub can_go_next: bool,
+    pub can_go_previous: bool,
+    pub can_play: bool,
+    pub can_pause: bool,
+    pub can_seek: bool,
+    pub can_control: bool, // When false, every other action is unavailable too
+}
+
+impl Default for MediaCapabilities {
+    fn default() -> Self {
+        MediaCapabilities {
+            can_go_next: true,
+            can_go_previous: true,
+            can_play: true,
+            can_pause: true,
+            can_seek: true,
+            can_control: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Track {
+    pub id: String, // Stable id chosen by the application
+    pub metadata: MediaMetadata,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Playlist {
+    pub id: String, // Stable id chosen by the application
+    pub name: String,
+    pub icon: Option<String>, // URI of the playlist icon
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -100,4 +233,122 @@ pub enum MediaControlEventType {
 pub struct InitializeMediaSessionRequest {
     pub app_id: String,
     pub app_name: String,
+    pub supported_uri_schemes: Option<Vec<String>>, // Overrides the plugin config
+    pub supported_mime_types: Option<Vec<String>>,  // Overrides the plugin config
+    #[serde(default)]
+    pub bind_to_window: bool,  // Tie the session to the calling window
+}
+
+/// A media session published by any application on the system.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SystemMediaSession {
+    pub id: String,                    // MPRIS bus name on Linux
+    pub identity: Option<String>,      // Human readable player name
+    pub desktop_entry: Option<String>, // Desktop file name without extension
+    pub status: PlaybackStatus,
+    pub position: f64, // Position in seconds
+    pub metadata: Option<MediaMetadata>,
+    pub capabilities: MediaCapabilities,
+}
+
+/// Fields of a system media session that changed. Only the changed fields are set.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionChange {
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<PlaybackStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub position: Option<f64>, // Position in seconds after a seek
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<MediaMetadata>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub capabilities: Option<MediaCapabilities>,
+}
+
+/// Change to the set of system media sessions, reported while watching.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SessionEvent {
+    Added(SystemMediaSession),
+    Removed(String), // Id of the session that went away
+    Changed(SessionChange),
+}
+
+/// Command sent to another application's media session.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SessionAction {
+    Play,
+    Pause,
+    PlayPause,
+    Stop,
+    Next,
+    Previous,
+    SeekBy(f64),      // Relative offset in seconds, negative seeks backwards
+    SetPosition(f64), // Absolute position in seconds within the current track
+    SetVolume(f64),   // Volume from 0.0 to 1.0
+    SetShuffle(bool),
+    SetRepeatMode(RepeatMode),
+}
+
+/// Details about the session created by `initialize_session`.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MediaSessionInfo {
+    pub bus_name: Option<String>, // MPRIS bus name that was acquired, Linux only
+}
+
+/// What happens when a desktop client asks the application to quit.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum QuitPolicy {
+    /// Exit through `AppHandle::exit`, which can still be prevented from
+    /// `RunEvent::ExitRequested`.
+    #[default]
+    Exit,
+    /// Emit `media://quit-requested` and forward a `Quit` control event,
+    /// leaving the decision to the application.
+    Event,
+}
+
+/// Plugin configuration, read from `plugins.media` in `tauri.conf.json`
+/// or passed to [`crate::init_with_config`].
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Config {
+    pub can_raise: bool, // Let desktop clients bring the main window to the front
+    pub can_quit: bool,  // Let desktop clients ask the application to quit
+    pub quit_policy: QuitPolicy,
+    pub supported_uri_schemes: Vec<String>, // Schemes accepted by OpenUri
+    pub supported_mime_types: Vec<String>,
+    pub player_priority: Vec<String>, // Players preferred when none is more recently active
+    pub ignored_players: Vec<String>, // Players never picked as the active session
+    pub minimum_rate: f64,            // Slowest playback rate desktop clients may request
+    pub maximum_rate: f64,            // Fastest playback rate desktop clients may request
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            can_raise: false,
+            can_quit: false,
+            quit_policy: QuitPolicy::default(),
+            supported_uri_schemes: vec![
+                "file".to_string(),
+                "http".to_string(),
+                "https".to_string(),
+            ],
+            supported_mime_types: vec![
+                "audio/mpeg".to_string(),
+                "audio/mp4".to_string(),
+                "audio/ogg".to_string(),
+            ],
+            player_priority: Vec::new(),
+            ignored_players: Vec::new(),
+            minimum_rate: 1.0,
+            maximum_rate: 1.0,
+        }
+    }
 }
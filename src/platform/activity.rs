@@ -0,0 +1,270 @@
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::time::Instant;
+
+/// Prefix shared by the bus names of all MPRIS players.
+const MPRIS_PREFIX: &str = "org.mpris.MediaPlayer2.";
+
+/// What we have observed of one player since we started watching the bus.
+#[derive(Debug, Default)]
+struct PlayerActivity {
+    playing: bool,
+    started_playing: Option<Instant>,
+    last_active: Option<Instant>,
+}
+
+/// Per-player activity history, keyed by well-known bus name.
+#[derive(Debug, Default)]
+pub(crate) struct ActivityLog {
+    players: HashMap<String, PlayerActivity>,
+}
+
+impl ActivityLog {
+    /// Records a player that was already on the bus when we started watching.
+    /// Its history is unknown, so it gets no timestamps.
+    pub(crate) fn seed(&mut self, name: String, playing: bool) {
+        self.players.entry(name).or_default().playing = playing;
+    }
+
+    /// Records a change of `PlaybackStatus`.
+    pub(crate) fn record_status(&mut self, name: &str, playing: bool) {
+        let now = Instant::now();
+        let player = self.players.entry(name.to_string()).or_default();
+        if playing && !player.playing {
+            player.started_playing = Some(now);
+        }
+        player.playing = playing;
+        player.last_active = Some(now);
+    }
+
+    /// Records any other sign of life: a new player, a property change or a seek.
+    pub(crate) fn record_activity(&mut self, name: &str) {
+        self.players
+            .entry(name.to_string())
+            .or_default()
+            .last_active = Some(Instant::now());
+    }
+
+    pub(crate) fn remove(&mut self, name: &str) {
+        self.players.remove(name);
+    }
+}
+
+/// Decides which player is the active session, modelled on playerctld: the
+/// player that most recently started playing, then the most recently active
+/// one, then the first match in the priority list. Ignored players are never
+/// picked.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct PlayerPolicy {
+    priority: Vec<String>,
+    ignored: Vec<String>,
+}
+
+impl PlayerPolicy {
+    pub(crate) fn new(priority: Vec<String>, ignored: Vec<String>) -> Self {
+        PlayerPolicy { priority, ignored }
+    }
+
+    pub(crate) fn ignores(&self, name: &str) -> bool {
+        self.ignored
+            .iter()
+            .any(|pattern| player_matches(pattern, name))
+    }
+
+    /// Sorts `names` from most to least preferred and drops ignored players.
+    /// Players the policy cannot tell apart keep their bus order.
+    pub(crate) fn rank(&self, mut names: Vec<String>, log: &ActivityLog) -> Vec<String> {
+        names.retain(|name| !self.ignores(name));
+        names.sort_by_cached_key(|name| {
+            let activity = log.players.get(name);
+            let playing = activity.is_some_and(|player| player.playing);
+            let started_playing = activity
+                .filter(|player| player.playing)
+                .and_then(|player| player.started_playing);
+            let last_active = activity.and_then(|player| player.last_active);
+            let priority = self
+                .priority
+                .iter()
+                .position(|pattern| player_matches(pattern, name))
+                .unwrap_or(usize::MAX);
+            (
+                Reverse(playing),
+                Reverse(started_playing),
+                Reverse(last_active),
+                priority,
+            )
+        });
+        names
+    }
+}
+
+/// Whether `pattern` names the player on `name`. Patterns may be a full bus
+/// name or the part after `org.mpris.MediaPlayer2.`, and a short name also
+/// matches that player's `.instance` names, so `vlc` covers
+/// `org.mpris.MediaPlayer2.vlc.instance1234`.
+fn player_matches(pattern: &str, name: &str) -> bool {
+    let short = name.strip_prefix(MPRIS_PREFIX).unwrap_or(name);
+    name == pattern
+        || short == pattern
+        || short
+            .strip_prefix(pattern)
+            .is_some_and(|rest| rest.starts_with('.'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names
+            .iter()
+            .map(|name| format!("{}{}", MPRIS_PREFIX, name))
+            .collect()
+    }
+
+    fn at(base: Instant, secs: u64) -> Option<Instant> {
+        Some(base + Duration::from_secs(secs))
+    }
+
+    fn insert(log: &mut ActivityLog, name: &str, activity: PlayerActivity) {
+        log.players
+            .insert(format!("{}{}", MPRIS_PREFIX, name), activity);
+    }
+
+    #[test]
+    fn playing_players_rank_before_recently_active_ones() {
+        let base = Instant::now();
+        let mut log = ActivityLog::default();
+        log.seed(format!("{}music", MPRIS_PREFIX), true);
+        insert(
+            &mut log,
+            "video",
+            PlayerActivity {
+                last_active: at(base, 10),
+                ..Default::default()
+            },
+        );
+
+        let ranked = PlayerPolicy::default().rank(names(&["video", "music"]), &log);
+        assert_eq!(ranked, names(&["music", "video"]));
+    }
+
+    #[test]
+    fn most_recently_started_player_ranks_first() {
+        let base = Instant::now();
+        let mut log = ActivityLog::default();
+        insert(
+            &mut log,
+            "early",
+            PlayerActivity {
+                playing: true,
+                started_playing: at(base, 1),
+                last_active: at(base, 20),
+            },
+        );
+        insert(
+            &mut log,
+            "late",
+            PlayerActivity {
+                playing: true,
+                started_playing: at(base, 5),
+                last_active: at(base, 5),
+            },
+        );
+
+        let ranked = PlayerPolicy::default().rank(names(&["early", "late"]), &log);
+        assert_eq!(ranked, names(&["late", "early"]));
+    }
+
+    #[test]
+    fn stopped_players_rank_by_last_activity() {
+        let base = Instant::now();
+        let mut log = ActivityLog::default();
+        // Started playing most recently, but has stopped since
+        insert(
+            &mut log,
+            "stopped",
+            PlayerActivity {
+                playing: false,
+                started_playing: at(base, 30),
+                last_active: at(base, 1),
+            },
+        );
+        insert(
+            &mut log,
+            "busy",
+            PlayerActivity {
+                last_active: at(base, 2),
+                ..Default::default()
+            },
+        );
+
+        let ranked = PlayerPolicy::default().rank(names(&["stopped", "busy", "idle"]), &log);
+        assert_eq!(ranked, names(&["busy", "stopped", "idle"]));
+    }
+
+    #[test]
+    fn priority_breaks_ties_and_unlisted_players_keep_bus_order() {
+        let policy = PlayerPolicy::new(
+            vec![format!("{}spotify", MPRIS_PREFIX), "vlc".to_string()],
+            Vec::new(),
+        );
+        let ranked = policy.rank(
+            names(&["b", "vlc.instance42", "a", "spotify"]),
+            &ActivityLog::default(),
+        );
+        assert_eq!(ranked, names(&["spotify", "vlc.instance42", "b", "a"]));
+    }
+
+    #[test]
+    fn activity_outranks_priority() {
+        let mut log = ActivityLog::default();
+        log.record_activity(&format!("{}other", MPRIS_PREFIX));
+        let policy = PlayerPolicy::new(vec!["spotify".to_string()], Vec::new());
+
+        let ranked = policy.rank(names(&["spotify", "other"]), &log);
+        assert_eq!(ranked, names(&["other", "spotify"]));
+    }
+
+    #[test]
+    fn ignored_players_are_dropped() {
+        let mut log = ActivityLog::default();
+        log.record_status(&format!("{}firefox.instance_1_84", MPRIS_PREFIX), true);
+        let policy = PlayerPolicy::new(Vec::new(), vec!["firefox".to_string()]);
+
+        let ranked = policy.rank(names(&["firefox.instance_1_84", "firefoxy", "mpv"]), &log);
+        assert_eq!(ranked, names(&["firefoxy", "mpv"]));
+    }
+
+    #[test]
+    fn patterns_match_full_short_and_instance_names() {
+        let name = "org.mpris.MediaPlayer2.vlc.instance1234";
+        assert!(player_matches(name, name));
+        assert!(player_matches("vlc.instance1234", name));
+        assert!(player_matches("vlc", name));
+        assert!(player_matches("vlc", "org.mpris.MediaPlayer2.vlc"));
+        assert!(player_matches(
+            "org.mpris.MediaPlayer2.vlc",
+            "org.mpris.MediaPlayer2.vlc"
+        ));
+        assert!(!player_matches("vl", name));
+        assert!(!player_matches("vlc", "org.mpris.MediaPlayer2.vlcx"));
+        assert!(!player_matches("vlc.instance12", name));
+    }
+
+    #[test]
+    fn restarting_playback_keeps_the_original_start_time() {
+        let name = format!("{}music", MPRIS_PREFIX);
+        let mut log = ActivityLog::default();
+        log.record_status(&name, true);
+        let started = log.players[&name].started_playing;
+        assert!(started.is_some());
+
+        log.record_status(&name, true);
+        assert_eq!(log.players[&name].started_playing, started);
+
+        log.remove(&name);
+        assert!(log.players.is_empty());
+    }
+}
@@ -0,0 +1,134 @@
+use std::collections::HashSet;
+use std::fs;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Number of images kept on disk before the least recently used ones that
+/// are no longer published are removed.
+const MAX_CACHED_IMAGES: usize = 32;
+
+/// Content-addressed store for raw artwork, used to hand out stable `file://`
+/// URLs for clients that only understand URLs. The same bytes always map to
+/// the same file, so re-publishing unchanged artwork never touches the disk
+/// and new artwork always gets a new URL.
+#[derive(Debug, Clone)]
+pub(crate) struct ArtworkCache {
+    dir: PathBuf,
+}
+
+impl ArtworkCache {
+    /// Creates a cache in `dir`, discarding anything a previous session left
+    /// behind. `dir` is named after the session's bus name, and caches of
+    /// `.instance<pid>` names whose process is gone are removed as well, since
+    /// a process that exits without tearing down its session leaves them.
+    pub(crate) fn new(dir: PathBuf) -> Self {
+        let _ = fs::remove_dir_all(&dir);
+        if let Some(root) = dir.parent() {
+            remove_stale_caches(root);
+        }
+        ArtworkCache { dir }
+    }
+
+    /// Returns a `file://` URL for `data`, writing it to the cache first if needed.
+    pub(crate) fn url_for(&self, data: &[u8]) -> Option<String> {
+        let mut hasher = DefaultHasher::new();
+        data.hash(&mut hasher);
+        let path = self.dir.join(format!(
+            "{:016x}.{}",
+            hasher.finish(),
+            sniff_extension(data)
+        ));
+
+        if path.exists() {
+            // Mark as recently used so pruning keeps it
+            if let Ok(file) = fs::File::options().append(true).open(&path) {
+                let _ = file.set_modified(SystemTime::now());
+            }
+        } else {
+            fs::create_dir_all(&self.dir).ok()?;
+            // Write under a temporary name so clients never read a partial image
+            let partial = path.with_extension("part");
+            fs::write(&partial, data).ok()?;
+            fs::rename(&partial, &path).ok()?;
+        }
+
+        Some(file_url(&path))
+    }
+
+    /// Removes the least recently used images beyond [`MAX_CACHED_IMAGES`],
+    /// except those whose URL is in `in_use`.
+    pub(crate) fn prune(&self, in_use: &HashSet<String>) {
+        let Ok(entries) = fs::read_dir(&self.dir) else {
+            return;
+        };
+
+        let mut files = entries
+            .filter_map(|entry| {
+                let entry = entry.ok()?;
+                let modified = entry.metadata().ok()?.modified().ok()?;
+                Some((modified, entry.path()))
+            })
+            .collect::<Vec<_>>();
+
+        if files.len() > MAX_CACHED_IMAGES {
+            files.sort_by_key(|(modified, _)| std::cmp::Reverse(*modified));
+            for (_, path) in files.drain(MAX_CACHED_IMAGES..) {
+                if !in_use.contains(&file_url(&path)) {
+                    let _ = fs::remove_file(path);
+                }
+            }
+        }
+    }
+
+    /// Deletes the cache directory and everything in it.
+    pub(crate) fn remove(&self) {
+        let _ = fs::remove_dir_all(&self.dir);
+    }
+}
+
+fn file_url(path: &Path) -> String {
+    format!("file://{}", path.display())
+}
+
+/// Removes the caches in `root` that belong to processes no longer running.
+fn remove_stale_caches(root: &Path) {
+    let Ok(entries) = fs::read_dir(root) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        let name = entry.file_name();
+        let Some(pid) = name.to_str().and_then(instance_pid) else {
+            continue;
+        };
+        if !Path::new("/proc").join(pid.to_string()).exists() {
+            let _ = fs::remove_dir_all(entry.path());
+        }
+    }
+}
+
+/// The process id in a bus name ending in `.instance<pid>`, optionally
+/// followed by `_<session id>`.
+fn instance_pid(bus_name: &str) -> Option<u32> {
+    let instance = bus_name.rsplit('.').next()?.strip_prefix("instance")?;
+    let digits = instance.split('_').next()?;
+    digits.parse().ok()
+}
+
+/// Picks a file extension from the image's magic bytes.
+fn sniff_extension(data: &[u8]) -> &'static str {
+    if data.starts_with(b"\x89PNG\r\n\x1a\n") {
+        "png"
+    } else if data.starts_with(&[0xff, 0xd8, 0xff]) {
+        "jpg"
+    } else if data.starts_with(b"GIF87a") || data.starts_with(b"GIF89a") {
+        "gif"
+    } else if data.len() >= 12 && &data[0..4] == b"RIFF" && &data[8..12] == b"WEBP" {
+        "webp"
+    } else if data.starts_with(b"BM") {
+        "bmp"
+    } else {
+        "img"
+    }
+}
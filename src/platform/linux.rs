@@ -1,551 +1,2503 @@
 use crate::models::*;
-use std::error::Error as StdError;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
 
 #[cfg(target_os = "linux")]
-use dbus::blocking::Connection;
+use dbus::arg::{PropMap, RefArg, Variant};
 #[cfg(target_os = "linux")]
-use dbus_crossroads::{Crossroads, IfaceBuilder, IfaceToken};
+use dbus::blocking::SyncConnection;
 #[cfg(target_os = "linux")]
-use std::collections::HashMap;
+use dbus::channel::{MatchingReceiver, Sender};
+#[cfg(target_os = "linux")]
+use dbus::message::MatchRule;
+#[cfg(target_os = "linux")]
+use dbus_crossroads::{Crossroads, IfaceBuilder};
+
+use super::activity::{ActivityLog, PlayerPolicy};
+use super::artwork::ArtworkCache;
+
+/// How long the dispatch and watcher threads block waiting for a message
+/// before they re-check whether they should still be running.
+const DISPATCH_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Largest difference, in seconds, between a reported position and the
+/// extrapolated one that is still treated as regular playback progress
+/// rather than a seek.
+const SEEK_TOLERANCE: f64 = 1.0;
+
+/// Object path prefix under which published track ids are advertised.
+const TRACK_PATH_PREFIX: &str = "/com/tauri/media/Track";
+
+/// `mpris:trackid` of metadata that is not tied to a track list entry.
+const CURRENT_TRACK_PATH: &str = "/com/tauri/media/CurrentTrack";
+
+/// Object path prefix under which published playlist ids are advertised.
+const PLAYLIST_PATH_PREFIX: &str = "/com/tauri/media/Playlist";
+
+/// Track id the MPRIS spec reserves for "no track".
+const NO_TRACK_PATH: &str = "/org/mpris/MediaPlayer2/TrackList/NoTrack";
+
+/// Session state read by the MPRIS property getters on every request, so
+/// clients always see the latest values pushed by the application.
+#[derive(Default)]
+struct MprisState {
+    metadata: Option<MediaMetadata>,
+    // `mpris:artUrl` of `metadata`, resolved when it is set so the getters
+    // never touch the artwork cache
+    art_url: Option<String>,
+    playback_info: Option<PlaybackInfo>,
+    // When `playback_info.position` was last reported by the application
+    position_updated: Option<Instant>,
+    tracks: Vec<Track>,
+    // `mpris:artUrl` of the listed tracks, keyed by track id
+    track_art_urls: HashMap<String, String>,
+    playlists: Vec<Playlist>,
+    active_playlist_id: Option<String>,
+    capabilities: MediaCapabilities,
+}
+
+impl MprisState {
+    /// Position in seconds extrapolated from the last reported one, the time
+    /// elapsed since and the playback rate.
+    fn current_position(&self) -> f64 {
+        self.position_at(Instant::now())
+    }
+
+    /// Position in seconds extrapolated to `now`.
+    fn position_at(&self, now: Instant) -> f64 {
+        let Some(info) = &self.playback_info else {
+            return 0.0;
+        };
+
+        let mut position = info.position;
+        if info.status == PlaybackStatus::Playing {
+            if let Some(updated) = self.position_updated {
+                position +=
+                    now.saturating_duration_since(updated).as_secs_f64() * info.playback_rate;
+            }
+        }
+
+        match self.metadata.as_ref().and_then(|meta| meta.duration) {
+            Some(duration) => position.min(duration),
+            None => position,
+        }
+    }
+
+    /// Stores `info` as reported by the application at `now`. Returns whether
+    /// its position departs from the extrapolated one far enough to be a seek.
+    fn report_playback_info(&mut self, info: PlaybackInfo, now: Instant) -> bool {
+        // The first report has nothing to compare against
+        let seeked = self.playback_info.is_some()
+            && (info.position - self.position_at(now)).abs() > SEEK_TOLERANCE;
+        self.playback_info = Some(info);
+        self.position_updated = Some(now);
+        seeked
+    }
+
+    /// Applies `change` to the stored playback info, with the position
+    /// brought up to `now` first, so changing only the status keeps the
+    /// progress made since the last report. Returns the new info and whether
+    /// it is a seek, or `None` before the application reported any.
+    fn update_playback_info(
+        &mut self,
+        now: Instant,
+        change: impl FnOnce(&mut PlaybackInfo),
+    ) -> Option<(PlaybackInfo, bool)> {
+        let mut info = self.playback_info.clone()?;
+        info.position = self.position_at(now);
+        change(&mut info);
+        let seeked = self.report_playback_info(info.clone(), now);
+        Some((info, seeked))
+    }
+
+    /// Object path of the track currently advertised in `Metadata`.
+    fn current_track_path(&self) -> dbus::Path<'static> {
+        let track_id = self.metadata.as_ref().and_then(|m| m.track_id.as_deref());
+        track_path(track_id)
+    }
+}
+
+type SharedState = Arc<Mutex<MprisState>>;
+
+type SharedEventHandler = Arc<Mutex<Option<Box<dyn Fn(MediaControlEvent) + Send>>>>;
+
+type SessionEventHandler = Arc<Mutex<Box<dyn Fn(SessionEvent) + Send>>>;
+
+/// Unique connection name to well-known MPRIS name, since signals carry the former.
+type SessionOwners = Arc<Mutex<HashMap<String, String>>>;
+
+/// A connection paired with the bus name of a remote player reachable on it.
+#[cfg(target_os = "linux")]
+type RemotePlayer = (Arc<SyncConnection>, String);
+
+/// Follows the playback status and activity of every player on the bus for
+/// the active-player policy. One tracker is shared by all of the
+/// application's sessions, and it runs from plugin setup onwards so the
+/// policy has history to work with by the time anything is queried.
+pub struct PlayerTracker {
+    activity: Arc<Mutex<ActivityLog>>,
+    policy: PlayerPolicy,
+    running: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl PlayerTracker {
+    pub fn new(config: &Config) -> Self {
+        let mut tracker = PlayerTracker {
+            activity: Arc::new(Mutex::new(ActivityLog::default())),
+            policy: PlayerPolicy::new(
+                config.player_priority.clone(),
+                config.ignored_players.clone(),
+            ),
+            running: Arc::new(AtomicBool::new(false)),
+            thread: None,
+        };
+        if let Err(e) = tracker.start() {
+            log::warn!("Failed to track MPRIS player activity: {}", e);
+        }
+        tracker
+    }
+
+    /// `names` without ignored players, most preferred first.
+    fn rank(&self, names: Vec<String>) -> crate::Result<Vec<String>> {
+        let activity = self
+            .activity
+            .lock()
+            .map_err(|_| crate::Error::platform("player activity is unavailable"))?;
+        Ok(self.policy.rank(names, &activity))
+    }
+
+    /// Spawns the thread that follows every player's playback status and
+    /// activity. Like the session watcher it has its own connection.
+    fn start(&mut self) -> crate::Result<()> {
+        let conn = Arc::new(session_bus()?);
+        let owners: SessionOwners = Arc::new(Mutex::new(HashMap::new()));
+        let activity = self.activity.clone();
+
+        conn.add_match(
+            MatchRule::new_signal("org.freedesktop.DBus", "NameOwnerChanged")
+                .with_sender("org.freedesktop.DBus"),
+            {
+                let owners = owners.clone();
+                let activity = activity.clone();
+                move |(name, old_owner, new_owner): (String, String, String),
+                      _: &SyncConnection,
+                      _: &dbus::Message| {
+                    if !name.starts_with("org.mpris.MediaPlayer2.") {
+                        return true;
+                    }
+                    if let (Ok(mut owners), Ok(mut activity)) = (owners.lock(), activity.lock()) {
+                        owners.remove(&old_owner);
+                        if new_owner.is_empty() {
+                            activity.remove(&name);
+                        } else {
+                            activity.record_activity(&name);
+                            owners.insert(new_owner, name);
+                        }
+                    }
+                    true
+                }
+            },
+        )?;
+
+        conn.add_match(
+            MatchRule::new_signal("org.freedesktop.DBus.Properties", "PropertiesChanged")
+                .with_path("/org/mpris/MediaPlayer2"),
+            {
+                let owners = owners.clone();
+                let activity = activity.clone();
+                move |(interface, changed, _): (String, PropMap, Vec<String>),
+                      _: &SyncConnection,
+                      msg: &dbus::Message| {
+                    if interface != "org.mpris.MediaPlayer2.Player" {
+                        return true;
+                    }
+                    let (Some(name), Ok(mut activity)) =
+                        (session_of(&owners, msg), activity.lock())
+                    else {
+                        return true;
+                    };
+                    match prop_str(&changed, "PlaybackStatus") {
+                        Some(status) => activity.record_status(&name, status == "Playing"),
+                        None => activity.record_activity(&name),
+                    }
+                    true
+                }
+            },
+        )?;
+
+        conn.add_match(
+            MatchRule::new_signal("org.mpris.MediaPlayer2.Player", "Seeked")
+                .with_path("/org/mpris/MediaPlayer2"),
+            {
+                let owners = owners.clone();
+                let activity = activity.clone();
+                move |_: (i64,), _: &SyncConnection, msg: &dbus::Message| {
+                    if let (Some(name), Ok(mut activity)) =
+                        (session_of(&owners, msg), activity.lock())
+                    {
+                        activity.record_activity(&name);
+                    }
+                    true
+                }
+            },
+        )?;
+
+        let running = Arc::new(AtomicBool::new(true));
+        self.running = running.clone();
+
+        let thread = std::thread::Builder::new()
+            .name("mpris-activity".to_string())
+            .spawn(move || {
+                // Players already on the bus are seeded here rather than in
+                // `new` so a slow player cannot hold up plugin setup. Signals
+                // are only handled by `process` below, so none are missed.
+                if let Ok(names) = list_player_names(&conn) {
+                    if let Ok(mut owners) = owners.lock() {
+                        owners.extend(read_owners(&conn, &names));
+                    }
+                    for name in names {
+                        let playing = read_session(&conn, name.clone())
+                            .is_some_and(|session| session.status == PlaybackStatus::Playing);
+                        if let Ok(mut activity) = activity.lock() {
+                            activity.seed(name, playing);
+                        }
+                    }
+                }
+
+                while running.load(Ordering::Acquire) {
+                    if let Err(e) = conn.process(DISPATCH_INTERVAL) {
+                        log::warn!("MPRIS activity tracker stopped: {}", e);
+                        break;
+                    }
+                }
+            })?;
+        self.thread = Some(thread);
+
+        Ok(())
+    }
+}
+
+impl Drop for PlayerTracker {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::Release);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
 
 pub struct LinuxMediaController {
     #[cfg(target_os = "linux")]
-    connection: Option<Connection>,
+    connection: Option<Arc<SyncConnection>>,
     #[cfg(target_os = "linux")]
     crossroads: Option<Arc<Mutex<Crossroads>>>,
-    event_handler: Option<Box<dyn Fn(MediaControlEvent) + Send>>,
-    metadata: Option<MediaMetadata>,
-    playback_info: Option<PlaybackInfo>,
+    #[cfg(target_os = "linux")]
+    bus_name: Option<String>,
+    #[cfg(target_os = "linux")]
+    dispatch_running: Arc<AtomicBool>,
+    #[cfg(target_os = "linux")]
+    dispatch_thread: Option<JoinHandle<()>>,
+    #[cfg(target_os = "linux")]
+    watch_running: Arc<AtomicBool>,
+    #[cfg(target_os = "linux")]
+    watch_thread: Option<JoinHandle<()>>,
+    players: Arc<PlayerTracker>,
+    event_handler: SharedEventHandler,
+    state: SharedState,
     app_id: String,
     app_name: String,
+    artwork_root: PathBuf,
+    artwork: Option<ArtworkCache>,
+    instance: Option<String>,
+    can_raise: bool,
+    can_quit: bool,
+    supported_uri_schemes: Vec<String>,
+    supported_mime_types: Vec<String>,
+    // MPRIS requires the advertised range to include 1.0
+    minimum_rate: f64,
+    maximum_rate: f64,
 }
 
 impl LinuxMediaController {
-    pub fn new() -> Self {
+    pub fn new(
+        config: &Config,
+        players: Arc<PlayerTracker>,
+        artwork_root: PathBuf,
+        instance: Option<&str>,
+    ) -> Self {
         LinuxMediaController {
             #[cfg(target_os = "linux")]
             connection: None,
             #[cfg(target_os = "linux")]
             crossroads: None,
-            event_handler: None,
-            metadata: None,
-            playback_info: None,
+            #[cfg(target_os = "linux")]
+            bus_name: None,
+            #[cfg(target_os = "linux")]
+            dispatch_running: Arc::new(AtomicBool::new(false)),
+            #[cfg(target_os = "linux")]
+            dispatch_thread: None,
+            #[cfg(target_os = "linux")]
+            watch_running: Arc::new(AtomicBool::new(false)),
+            #[cfg(target_os = "linux")]
+            watch_thread: None,
+            players,
+            event_handler: Arc::new(Mutex::new(None)),
+            state: Arc::new(Mutex::new(MprisState::default())),
             app_id: String::new(),
             app_name: String::new(),
+            artwork_root,
+            artwork: None,
+            instance: instance.map(str::to_string),
+            can_raise: config.can_raise,
+            can_quit: config.can_quit,
+            supported_uri_schemes: config.supported_uri_schemes.clone(),
+            supported_mime_types: config.supported_mime_types.clone(),
+            minimum_rate: config.minimum_rate.min(1.0),
+            maximum_rate: config.maximum_rate.max(1.0),
         }
     }
 
     #[cfg(target_os = "linux")]
-    fn setup_mpris(&mut self) -> Result<(), Box<dyn StdError>> {
-        let conn = Connection::new_session()?;
-        let name = format!("org.mpris.MediaPlayer2.{}", self.app_id);
-        conn.request_name(&name, false, true, false)?;
+    fn setup_mpris(&mut self) -> crate::Result<()> {
+        // Re-initializing replaces the previous session entirely
+        self.teardown_mpris();
+
+        let conn = Arc::new(session_bus()?);
+        let name = request_bus_name(&conn, &self.app_id, self.instance.as_deref())?;
+        // Each bus name gets its own directory so concurrent instances never
+        // remove each other's artwork
+        let artwork = ArtworkCache::new(self.artwork_root.join(&name));
 
         let mut cr = Crossroads::new();
+        let handler = self.event_handler.clone();
 
         // MediaPlayer2 interface
-        let iface_token = cr.register("org.mpris.MediaPlayer2", |b: &mut IfaceBuilder<()>| {
-            b.property("CanQuit").get(|_, _| Ok(false));
-            b.property("CanRaise").get(|_, _| Ok(false));
-            b.property("HasTrackList").get(|_, _| Ok(false));
-            b.property("Identity").get({
-                let app_name = self.app_name.clone();
-                move |_, _| Ok(app_name.clone())
-            });
-            b.property("SupportedUriSchemes")
-                .get(|_, _| Ok(vec!["file", "http", "https"]));
-            b.property("SupportedMimeTypes")
-                .get(|_, _| Ok(vec!["audio/mpeg", "audio/mp4", "audio/ogg"]));
-        });
-
-        // MediaPlayer2.Player interface
-        let player_iface_token = cr.register(
-            "org.mpris.MediaPlayer2.Player",
-            |b: &mut IfaceBuilder<()>| {
+        let iface_token = cr.register(
+            "org.mpris.MediaPlayer2",
+            |b: &mut IfaceBuilder<SharedState>| {
                 // Methods
-                b.method("Play", (), (), |_, _, _: ()| {
-                    // Handle play event
-                    Ok(())
-                });
+                // The spec asks for Raise and Quit to be no-ops when the
+                // matching capability is not advertised
+                for (method, enabled, event_type) in [
+                    ("Raise", self.can_raise, MediaControlEventType::Raise),
+                    ("Quit", self.can_quit, MediaControlEventType::Quit),
+                ] {
+                    let handler = handler.clone();
+                    b.method(method, (), (), move |_, _, _: ()| {
+                        if enabled {
+                            dispatch_event(&handler, event_type.clone());
+                        }
+                        Ok(())
+                    });
+                }
 
-                b.method("Pause", (), (), |_, _, _: ()| {
-                    // Handle pause event
-                    Ok(())
+                b.method("OpenUri", ("Uri",), (), {
+                    let handler = handler.clone();
+                    let schemes = self.supported_uri_schemes.clone();
+                    move |_, _, (uri,): (String,)| {
+                        if !uri_scheme_supported(&uri, &schemes) {
+                            return Err(dbus::MethodErr::invalid_arg(&uri));
+                        }
+                        dispatch_event(&handler, MediaControlEventType::OpenUri(uri));
+                        Ok(())
+                    }
                 });
 
-                b.method("PlayPause", (), (), |_, _, _: ()| {
-                    // Handle play/pause toggle
-                    Ok(())
+                // Properties
+                let (can_quit, can_raise) = (self.can_quit, self.can_raise);
+                b.property("CanQuit").get(move |_, _| Ok(can_quit));
+                b.property("CanRaise").get(move |_, _| Ok(can_raise));
+                b.property("HasTrackList").get(|_, _| Ok(true));
+                b.property("Identity").get({
+                    let app_name = self.app_name.clone();
+                    move |_, _| Ok(app_name.clone())
                 });
-
-                b.method("Stop", (), (), |_, _, _: ()| {
-                    // Handle stop event
-                    Ok(())
+                b.property("SupportedUriSchemes").get({
+                    let schemes = self.supported_uri_schemes.clone();
+                    move |_, _| Ok(schemes.clone())
                 });
-
-                b.method("Next", (), (), |_, _, _: ()| {
-                    // Handle next track
-                    Ok(())
+                b.property("SupportedMimeTypes").get({
+                    let mime_types = self.supported_mime_types.clone();
+                    move |_, _| Ok(mime_types.clone())
                 });
+            },
+        );
 
-                b.method("Previous", (), (), |_, _, _: ()| {
-                    // Handle previous track
-                    Ok(())
-                });
+        // MediaPlayer2.Player interface
+        let player_iface_token = cr.register(
+            "org.mpris.MediaPlayer2.Player",
+            |b: &mut IfaceBuilder<SharedState>| {
+                // Methods
+                // Calls for actions the session does not currently offer have no effect
+                for (method, capability, event_type) in [
+                    ("Play", "CanPlay", MediaControlEventType::Play),
+                    ("Pause", "CanPause", MediaControlEventType::Pause),
+                    ("PlayPause", "CanPause", MediaControlEventType::PlayPause),
+                    ("Stop", "CanControl", MediaControlEventType::Stop),
+                    ("Next", "CanGoNext", MediaControlEventType::Next),
+                    ("Previous", "CanGoPrevious", MediaControlEventType::Previous),
+                ] {
+                    let handler = handler.clone();
+                    b.method(method, (), (), move |_, state: &mut SharedState, _: ()| {
+                        if capability_enabled(&lock_state(state)?.capabilities, capability) {
+                            dispatch_event(&handler, event_type.clone());
+                        }
+                        Ok(())
+                    });
+                }
 
-                b.method("Seek", ("offset",), (), |_, _, (offset,): (i64,)| {
-                    // Handle seek
-                    Ok(())
+                b.method("Seek", ("Offset",), (), {
+                    let handler = handler.clone();
+                    move |_, state: &mut SharedState, (offset,): (i64,)| {
+                        if capability_enabled(&lock_state(state)?.capabilities, "CanSeek") {
+                            dispatch_event(
+                                &handler,
+                                MediaControlEventType::SeekBy(offset as f64 / 1_000_000.0),
+                            );
+                        }
+                        Ok(())
+                    }
                 });
 
-                b.method(
-                    "SetPosition",
-                    ("track_id", "position"),
-                    (),
-                    |_, _, (_track_id, _position): (String, i64)| {
-                        // Handle position change
+                b.method("SetPosition", ("TrackId", "Position"), (), {
+                    let handler = handler.clone();
+                    move |_,
+                          state: &mut SharedState,
+                          (track_id, position): (dbus::Path<'static>, i64)| {
+                        let (can_seek, current_track) = {
+                            let state = lock_state(state)?;
+                            let can_seek = capability_enabled(&state.capabilities, "CanSeek");
+                            (can_seek, state.current_track_path())
+                        };
+                        // Per the spec, stale track ids and out-of-range positions are ignored
+                        if can_seek && track_id == current_track && position >= 0 {
+                            dispatch_event(
+                                &handler,
+                                MediaControlEventType::SetPosition(position as f64 / 1_000_000.0),
+                            );
+                        }
                         Ok(())
-                    },
-                );
+                    }
+                });
 
                 // Properties
-                b.property("PlaybackStatus").get({
-                    let status = self
-                        .playback_info
-                        .as_ref()
-                        .map(|info| match info.status {
-                            PlaybackStatus::Playing => "Playing",
-                            PlaybackStatus::Paused => "Paused",
-                            PlaybackStatus::Stopped => "Stopped",
-                        })
-                        .unwrap_or("Stopped");
-                    move |_, _| Ok(status.to_string())
-                });
+                b.property("PlaybackStatus")
+                    .get(|_, state: &mut SharedState| {
+                        let state = lock_state(state)?;
+                        Ok(state
+                            .playback_info
+                            .as_ref()
+                            .map(|info| playback_status_str(info.status))
+                            .unwrap_or("Stopped")
+                            .to_string())
+                    });
 
                 b.property("LoopStatus")
-                    .get({
-                        let repeat = self
+                    .get(|_, state: &mut SharedState| {
+                        let state = lock_state(state)?;
+                        Ok(state
                             .playback_info
                             .as_ref()
-                            .map(|info| match info.repeat_mode {
-                                RepeatMode::None => "None",
-                                RepeatMode::Track => "Track",
-                                RepeatMode::List => "Playlist",
-                            })
-                            .unwrap_or("None");
-                        move |_, _| Ok(repeat.to_string())
+                            .map(|info| loop_status_str(info.repeat_mode))
+                            .unwrap_or("None")
+                            .to_string())
                     })
-                    .set(|_, _, value: String| {
-                        // Handle loop status change
-                        Ok(Some(value))
+                    .set({
+                        let handler = handler.clone();
+                        move |_, state: &mut SharedState, value: String| {
+                            if !capability_enabled(&lock_state(state)?.capabilities, "CanControl") {
+                                return Ok(None);
+                            }
+                            let repeat_mode = match value.as_str() {
+                                "None" => RepeatMode::None,
+                                "Track" => RepeatMode::Track,
+                                "Playlist" => RepeatMode::List,
+                                _ => return Err(dbus::MethodErr::invalid_arg(&value)),
+                            };
+                            // The application confirms the change through set_playback_info
+                            dispatch_event(
+                                &handler,
+                                MediaControlEventType::SetRepeatMode(repeat_mode),
+                            );
+                            Ok(None)
+                        }
                     });
 
                 b.property("Rate")
-                    .get({
-                        let rate = self
+                    .get(|_, state: &mut SharedState| {
+                        let state = lock_state(state)?;
+                        Ok(state
                             .playback_info
                             .as_ref()
                             .map(|info| info.playback_rate)
-                            .unwrap_or(1.0);
-                        move |_, _| Ok(rate)
+                            .unwrap_or(1.0))
                     })
-                    .set(|_, _, value: f64| {
-                        // Handle rate change
-                        Ok(Some(value))
+                    .set({
+                        let handler = handler.clone();
+                        let (minimum_rate, maximum_rate) = (self.minimum_rate, self.maximum_rate);
+                        move |_, state: &mut SharedState, value: f64| {
+                            if !capability_enabled(&lock_state(state)?.capabilities, "CanControl") {
+                                return Ok(None);
+                            }
+                            // A rate of 0.0 must be treated as a pause request, and
+                            // rates outside the advertised range must be ignored
+                            let event_type = if value == 0.0 {
+                                MediaControlEventType::Pause
+                            } else if (minimum_rate..=maximum_rate).contains(&value) {
+                                MediaControlEventType::SetPlaybackRate(value)
+                            } else {
+                                return Ok(None);
+                            };
+                            dispatch_event(&handler, event_type);
+                            Ok(None)
+                        }
                     });
 
                 b.property("Shuffle")
-                    .get({
-                        let shuffle = self
+                    .get(|_, state: &mut SharedState| {
+                        let state = lock_state(state)?;
+                        Ok(state
                             .playback_info
                             .as_ref()
                             .map(|info| info.shuffle)
-                            .unwrap_or(false);
-                        move |_, _| Ok(shuffle)
+                            .unwrap_or(false))
                     })
-                    .set(|_, _, value: bool| {
-                        // Handle shuffle change
-                        Ok(Some(value))
+                    .set({
+                        let handler = handler.clone();
+                        move |_, state: &mut SharedState, value: bool| {
+                            if !capability_enabled(&lock_state(state)?.capabilities, "CanControl") {
+                                return Ok(None);
+                            }
+                            dispatch_event(&handler, MediaControlEventType::SetShuffle(value));
+                            Ok(None)
+                        }
                     });
 
-                b.property("Metadata").get({
-                    let metadata = self.create_metadata_dict();
-                    move |_, _| Ok(metadata.clone())
+                b.property("Metadata").get(|_, state: &mut SharedState| {
+                    let state = lock_state(state)?;
+                    let track_id = state.metadata.as_ref().and_then(|m| m.track_id.as_deref());
+                    Ok(create_metadata_dict(
+                        state.metadata.as_ref(),
+                        track_id,
+                        state.art_url.as_deref(),
+                    ))
                 });
 
                 b.property("Volume")
-                    .get(|_, _| Ok(1.0_f64))
-                    .set(|_, _, value: f64| {
-                        // Handle volume change
-                        Ok(Some(value))
+                    .get(|_, state: &mut SharedState| {
+                        let state = lock_state(state)?;
+                        Ok(state
+                            .playback_info
+                            .as_ref()
+                            .map(|info| info.volume)
+                            .unwrap_or(1.0))
+                    })
+                    .set({
+                        let handler = handler.clone();
+                        move |_, state: &mut SharedState, value: f64| {
+                            if !capability_enabled(&lock_state(state)?.capabilities, "CanControl") {
+                                return Ok(None);
+                            }
+                            // Negative volumes are treated as muted
+                            dispatch_event(
+                                &handler,
+                                MediaControlEventType::SetVolume(value.max(0.0)),
+                            );
+                            Ok(None)
+                        }
                     });
 
-                b.property("Position").get({
-                    let position = self
-                        .playback_info
-                        .as_ref()
-                        .map(|info| (info.position * 1_000_000.0) as i64)
-                        .unwrap_or(0);
-                    move |_, _| Ok(position)
-                });
+                // Position changes are announced through Seeked, never PropertiesChanged
+                b.property("Position")
+                    .get(|_, state: &mut SharedState| {
+                        let state = lock_state(state)?;
+                        Ok((state.current_position() * 1_000_000.0) as i64)
+                    })
+                    .emits_changed_false();
+
+                let (minimum_rate, maximum_rate) = (self.minimum_rate, self.maximum_rate);
+                b.property("MinimumRate").get(move |_, _| Ok(minimum_rate));
+                b.property("MaximumRate").get(move |_, _| Ok(maximum_rate));
+
+                for (property, _) in CAPABILITY_PROPERTIES {
+                    b.property(property).get(move |_, state: &mut SharedState| {
+                        Ok(capability_enabled(
+                            &lock_state(state)?.capabilities,
+                            property,
+                        ))
+                    });
+                }
 
-                b.property("MinimumRate").get(|_, _| Ok(1.0_f64));
+                // Signals
+                b.signal::<(i64,), _>("Seeked", ("Position",));
+            },
+        );
 
-                b.property("MaximumRate").get(|_, _| Ok(1.0_f64));
+        // MediaPlayer2.TrackList interface
+        let track_list_iface_token = cr.register(
+            "org.mpris.MediaPlayer2.TrackList",
+            |b: &mut IfaceBuilder<SharedState>| {
+                // Methods
+                b.method("GetTracksMetadata", ("TrackIds",), ("Metadata",), {
+                    move |_, state: &mut SharedState, (track_ids,): (Vec<dbus::Path<'static>>,)| {
+                        let state = lock_state(state)?;
+                        let metadata = track_ids
+                            .iter()
+                            .filter_map(|path| track_id_from_path(path))
+                            .filter_map(|id| state.tracks.iter().find(|t| t.id == id))
+                            .map(|track| {
+                                create_metadata_dict(
+                                    Some(&track.metadata),
+                                    Some(&track.id),
+                                    state.track_art_urls.get(&track.id).map(String::as_str),
+                                )
+                            })
+                            .collect::<Vec<_>>();
+                        Ok((metadata,))
+                    }
+                });
 
-                b.property("CanGoNext").get(|_, _| Ok(true));
+                b.method("AddTrack", ("Uri", "AfterTrack", "SetAsCurrent"), (), {
+                    let handler = handler.clone();
+                    move |_,
+                          _,
+                          (uri, after_track, set_as_current): (
+                        String,
+                        dbus::Path<'static>,
+                        bool,
+                    )| {
+                        dispatch_event(
+                            &handler,
+                            MediaControlEventType::AddTrack {
+                                uri,
+                                after_track_id: track_id_from_path(&after_track),
+                                set_as_current,
+                            },
+                        );
+                        Ok(())
+                    }
+                });
 
-                b.property("CanGoPrevious").get(|_, _| Ok(true));
+                b.method("RemoveTrack", ("TrackId",), (), {
+                    let handler = handler.clone();
+                    move |_, state: &mut SharedState, (track_id,): (dbus::Path<'static>,)| {
+                        let track_id = known_track_id(&*lock_state(state)?, &track_id);
+                        if let Some(track_id) = track_id {
+                            dispatch_event(&handler, MediaControlEventType::RemoveTrack(track_id));
+                        }
+                        Ok(())
+                    }
+                });
 
-                b.property("CanPlay").get(|_, _| Ok(true));
+                b.method("GoTo", ("TrackId",), (), {
+                    let handler = handler.clone();
+                    move |_, state: &mut SharedState, (track_id,): (dbus::Path<'static>,)| {
+                        // Unknown ids are ignored, as the spec requires
+                        let track_id = known_track_id(&*lock_state(state)?, &track_id);
+                        if let Some(track_id) = track_id {
+                            dispatch_event(&handler, MediaControlEventType::GoTo(track_id));
+                        }
+                        Ok(())
+                    }
+                });
 
-                b.property("CanPause").get(|_, _| Ok(true));
+                // Properties
+                b.property("Tracks")
+                    .get(|_, state: &mut SharedState| {
+                        let state = lock_state(state)?;
+                        Ok(state
+                            .tracks
+                            .iter()
+                            .map(|t| track_path(Some(&t.id)))
+                            .collect::<Vec<_>>())
+                    })
+                    .emits_changed_invalidates();
 
-                b.property("CanSeek").get(|_, _| Ok(true));
+                b.property("CanEditTracks").get(|_, _| Ok(true));
 
-                b.property("CanControl").get(|_, _| Ok(true));
+                // Signals
+                b.signal::<(Vec<dbus::Path<'static>>, dbus::Path<'static>), _>(
+                    "TrackListReplaced",
+                    ("Tracks", "CurrentTrack"),
+                );
+                b.signal::<(PropMap, dbus::Path<'static>), _>(
+                    "TrackAdded",
+                    ("Metadata", "AfterTrack"),
+                );
+                b.signal::<(dbus::Path<'static>,), _>("TrackRemoved", ("TrackId",));
+                b.signal::<(dbus::Path<'static>, PropMap), _>(
+                    "TrackMetadataChanged",
+                    ("TrackId", "Metadata"),
+                );
             },
         );
 
-        cr.insert(
-            "/org/mpris/MediaPlayer2",
-            &[iface_token, player_iface_token],
-            (),
-        );
-
-        self.connection = Some(conn);
-        self.crossroads = Some(Arc::new(Mutex::new(cr)));
-
-        Ok(())
-    }
+        // MediaPlayer2.Playlists interface
+        let playlists_iface_token = cr.register(
+            "org.mpris.MediaPlayer2.Playlists",
+            |b: &mut IfaceBuilder<SharedState>| {
+                // Methods
+                b.method("ActivatePlaylist", ("PlaylistId",), (), {
+                    let handler = handler.clone();
+                    move |_, state: &mut SharedState, (path,): (dbus::Path<'static>,)| {
+                        let playlist_id = id_from_path(PLAYLIST_PATH_PREFIX, &path)
+                            .filter(|id| {
+                                lock_state(state)
+                                    .is_ok_and(|s| s.playlists.iter().any(|p| &p.id == id))
+                            })
+                            .ok_or_else(|| dbus::MethodErr::invalid_arg(&path))?;
+                        dispatch_event(
+                            &handler,
+                            MediaControlEventType::ActivatePlaylist(playlist_id),
+                        );
+                        Ok(())
+                    }
+                });
 
-    #[cfg(target_os = "linux")]
-    fn create_metadata_dict(
-        &self,
-    ) -> HashMap<String, dbus::arg::Variant<Box<dyn dbus::arg::RefArg>>> {
-        use dbus::arg::Variant;
+                b.method(
+                    "GetPlaylists",
+                    ("Index", "MaxCount", "Order", "ReverseOrder"),
+                    ("Playlists",),
+                    |_, state: &mut SharedState, args: (u32, u32, String, bool)| {
+                        let (index, max_count, order, reverse_order) = args;
+                        let state = lock_state(state)?;
+                        let mut playlists = state.playlists.iter().collect::<Vec<_>>();
+                        // Playlists are kept in the order the application published them,
+                        // which is the only ordering besides alphabetical that we can honor
+                        if order == "Alphabetical" {
+                            playlists.sort_by_key(|p| p.name.to_lowercase());
+                        }
+                        if reverse_order {
+                            playlists.reverse();
+                        }
 
-        let mut metadata = HashMap::new();
+                        let playlists = playlists
+                            .into_iter()
+                            .skip(index as usize)
+                            .take(max_count as usize)
+                            .map(playlist_struct)
+                            .collect::<Vec<_>>();
+                        Ok((playlists,))
+                    },
+                );
 
-        if let Some(meta) = &self.metadata {
-            metadata.insert(
-                "mpris:trackid".to_string(),
-                Variant(Box::new(format!("/org/mpris/MediaPlayer2/Track/{}", 1))
-                    as Box<dyn dbus::arg::RefArg>),
-            );
+                // Properties
+                b.property("PlaylistCount")
+                    .get(
+                        |_, state: &mut SharedState| Ok(lock_state(state)?.playlists.len() as u32),
+                    );
 
-            metadata.insert(
-                "xesam:title".to_string(),
-                Variant(Box::new(meta.title.clone()) as Box<dyn dbus::arg::RefArg>),
-            );
+                b.property("Orderings")
+                    .get(|_, _| Ok(vec!["UserDefined".to_string(), "Alphabetical".to_string()]));
 
-            if let Some(artist) = &meta.artist {
-                metadata.insert(
-                    "xesam:artist".to_string(),
-                    Variant(Box::new(vec![artist.clone()]) as Box<dyn dbus::arg::RefArg>),
-                );
-            }
+                b.property("ActivePlaylist")
+                    .get(|_, state: &mut SharedState| {
+                        Ok(active_playlist_struct(&*lock_state(state)?))
+                    });
 
-            if let Some(album) = &meta.album {
-                metadata.insert(
-                    "xesam:album".to_string(),
-                    Variant(Box::new(album.clone()) as Box<dyn dbus::arg::RefArg>),
+                // Signals
+                b.signal::<((dbus::Path<'static>, String, String),), _>(
+                    "PlaylistChanged",
+                    ("Playlist",),
                 );
-            }
+            },
+        );
 
-            if let Some(album_artist) = &meta.album_artist {
-                metadata.insert(
-                    "xesam:albumArtist".to_string(),
-                    Variant(Box::new(vec![album_artist.clone()]) as Box<dyn dbus::arg::RefArg>),
-                );
-            }
+        cr.insert(
+            "/org/mpris/MediaPlayer2",
+            &[
+                iface_token,
+                player_iface_token,
+                track_list_iface_token,
+                playlists_iface_token,
+            ],
+            self.state.clone(),
+        );
 
-            if let Some(duration) = meta.duration {
-                metadata.insert(
-                    "mpris:length".to_string(),
-                    Variant(Box::new((duration * 1_000_000.0) as i64) as Box<dyn dbus::arg::RefArg>)
-                );
-            }
+        let cr = Arc::new(Mutex::new(cr));
+        self.start_dispatch(conn.clone(), cr.clone())?;
 
-            // Handle artwork - MPRIS primarily uses URLs
-            if let Some(artwork_url) = &meta.artwork_url {
-                metadata.insert(
-                    "mpris:artUrl".to_string(),
-                    Variant(Box::new(artwork_url.clone()) as Box<dyn dbus::arg::RefArg>),
-                );
-            } else if let Some(artwork_data) = &meta.artwork_data {
-                // For raw image data, we need to save it temporarily and provide a file:// URL
-                // This is a simplified approach - in production you might want to use a proper temp file
-                use std::fs;
-                use std::path::PathBuf;
-
-                let temp_dir = std::env::temp_dir();
-                let artwork_path = temp_dir.join(format!("mpris_artwork_{}.jpg", self.app_id));
-
-                // Decode base64 if needed (artwork_data is already Vec<u8>)
-                if let Ok(_) = fs::write(&artwork_path, artwork_data) {
-                    let file_url = format!("file://{}", artwork_path.display());
-                    metadata.insert(
-                        "mpris:artUrl".to_string(),
-                        Variant(Box::new(file_url) as Box<dyn dbus::arg::RefArg>),
-                    );
-                }
-            }
-        }
+        self.connection = Some(conn);
+        self.crossroads = Some(cr);
+        self.bus_name = Some(name);
+        self.artwork = Some(artwork);
 
-        metadata
+        // The new cache starts out empty, so artwork published before
+        // re-initializing is written again
+        self.refresh_art_urls()
     }
-}
 
-impl super::MediaController for LinuxMediaController {
-    fn initialize_session(
+    /// Spawns the thread that owns the receive side of the connection and
+    /// serves incoming method calls from the crossroads tree until
+    /// `teardown_mpris` is called. Control events are handed to the event
+    /// handler from this thread, so the controller thread stays free to
+    /// serve calls the handler makes in turn.
+    #[cfg(target_os = "linux")]
+    fn start_dispatch(
         &mut self,
-        app_id: String,
-        app_name: String,
-    ) -> Result<(), Box<dyn StdError>> {
-        self.app_id = app_id;
-        self.app_name = app_name;
+        conn: Arc<SyncConnection>,
+        cr: Arc<Mutex<Crossroads>>,
+    ) -> crate::Result<()> {
+        conn.start_receive(
+            MatchRule::new_method_call(),
+            Box::new(move |msg, conn| {
+                if let Ok(mut cr) = cr.lock() {
+                    let _ = cr.handle_message(msg, conn);
+                }
+                true
+            }),
+        );
 
-        #[cfg(target_os = "linux")]
-        {
-            self.setup_mpris()?;
-        }
+        let running = Arc::new(AtomicBool::new(true));
+        self.dispatch_running = running.clone();
+
+        let thread = std::thread::Builder::new()
+            .name("mpris-dispatch".to_string())
+            .spawn(move || {
+                while running.load(Ordering::Acquire) {
+                    if let Err(e) = conn.process(DISPATCH_INTERVAL) {
+                        log::warn!("MPRIS dispatch loop stopped: {}", e);
+                        break;
+                    }
+                }
+            })?;
+        self.dispatch_thread = Some(thread);
 
         Ok(())
     }
 
-    fn set_metadata(&mut self, metadata: MediaMetadata) -> Result<(), Box<dyn StdError>> {
-        self.metadata = Some(metadata);
-
-        #[cfg(target_os = "linux")]
-        {
-            // Send PropertiesChanged signal
-            if let Some(conn) = &self.connection {
-                let msg = dbus::Message::signal(
-                    &dbus::Path::from("/org/mpris/MediaPlayer2"),
-                    &"org.freedesktop.DBus.Properties".into(),
-                    &"PropertiesChanged".into(),
-                )
-                .append1("org.mpris.MediaPlayer2.Player")
-                .append2(
-                    vec![("Metadata", self.create_metadata_dict())]
-                        .into_iter()
-                        .collect::<HashMap<_, _>>(),
-                    Vec::<String>::new(),
-                );
+    /// Stops the dispatch thread and releases the bus name. Safe to call
+    /// when no session is active.
+    #[cfg(target_os = "linux")]
+    fn teardown_mpris(&mut self) {
+        self.dispatch_running.store(false, Ordering::Release);
+        if let Some(thread) = self.dispatch_thread.take() {
+            let _ = thread.join();
+        }
 
-                let _ = conn.send(msg);
-            }
+        if let (Some(conn), Some(name)) = (&self.connection, self.bus_name.take()) {
+            let _ = conn.release_name(name.as_str());
         }
 
-        Ok(())
-    }
+        self.connection = None;
+        self.crossroads = None;
 
-    fn set_playback_info(&mut self, info: PlaybackInfo) -> Result<(), Box<dyn StdError>> {
-        self.playback_info = Some(info);
+        if let Some(artwork) = self.artwork.take() {
+            artwork.remove();
+        }
+    }
 
-        #[cfg(target_os = "linux")]
-        {
-            // Send PropertiesChanged signal for playback status
-            if let Some(conn) = &self.connection {
-                let status = match info.status {
-                    PlaybackStatus::Playing => "Playing",
-                    PlaybackStatus::Paused => "Paused",
-                    PlaybackStatus::Stopped => "Stopped",
-                };
+    /// The session's connection, or a short-lived one when no session is active
+    /// so that other players can be queried before `initialize_session`.
+    #[cfg(target_os = "linux")]
+    fn bus_connection(&self) -> crate::Result<Arc<SyncConnection>> {
+        match &self.connection {
+            Some(conn) => Ok(conn.clone()),
+            None => Ok(Arc::new(session_bus()?)),
+        }
+    }
 
-                let mut changed = HashMap::new();
-                changed.insert("PlaybackStatus", dbus::arg::Variant(status));
-                changed.insert(
-                    "Position",
-                    dbus::arg::Variant((info.position * 1_000_000.0) as i64),
-                );
-                changed.insert("Rate", dbus::arg::Variant(info.playback_rate));
-                changed.insert("Shuffle", dbus::arg::Variant(info.shuffle));
+    /// Other players on the bus, most preferred first according to the
+    /// active-player policy. Ignored players and every session of this
+    /// process are left out.
+    #[cfg(target_os = "linux")]
+    fn ranked_players(&self, conn: &SyncConnection) -> crate::Result<Vec<String>> {
+        let mut names = list_player_names(conn)?;
+        names.retain(|name| !owned_by_this_process(conn, name));
+        self.players.rank(names)
+    }
 
-                let loop_status = match info.repeat_mode {
-                    RepeatMode::None => "None",
-                    RepeatMode::Track => "Track",
-                    RepeatMode::List => "Playlist",
-                };
-                changed.insert("LoopStatus", dbus::arg::Variant(loop_status));
+    /// The player the system-session getters and controls target by default.
+    #[cfg(target_os = "linux")]
+    fn active_player(&self) -> crate::Result<Option<RemotePlayer>> {
+        let conn = self.bus_connection()?;
+        let name = self.ranked_players(&conn)?.into_iter().next();
+        Ok(name.map(|name| (conn, name)))
+    }
 
-                let msg = dbus::Message::signal(
-                    &dbus::Path::from("/org/mpris/MediaPlayer2"),
-                    &"org.freedesktop.DBus.Properties".into(),
-                    &"PropertiesChanged".into(),
-                )
-                .append1("org.mpris.MediaPlayer2.Player")
-                .append2(changed, Vec::<String>::new());
+    fn lock_state(&self) -> crate::Result<std::sync::MutexGuard<'_, MprisState>> {
+        self.state
+            .lock()
+            .map_err(|_| crate::Error::platform("media session state is unavailable"))
+    }
 
-                let _ = conn.send(msg);
-            }
+    /// The `mpris:artUrl` for `metadata`: its own URL, or a `file://` URL for
+    /// raw artwork written to the session's cache.
+    fn resolve_art_url(&self, metadata: &MediaMetadata) -> Option<String> {
+        if metadata.artwork_url.is_some() {
+            return metadata.artwork_url.clone();
         }
+        let data = metadata.artwork_data.as_ref()?;
+        self.artwork.as_ref()?.url_for(data)
+    }
 
-        Ok(())
+    /// [`Self::resolve_art_url`] for every track that has artwork, by track id.
+    fn resolve_track_art_urls(&self, tracks: &[Track]) -> HashMap<String, String> {
+        tracks
+            .iter()
+            .filter_map(|track| Some((track.id.clone(), self.resolve_art_url(&track.metadata)?)))
+            .collect()
     }
 
-    fn set_playback_status(&mut self, status: PlaybackStatus) -> Result<(), Box<dyn StdError>> {
-        if let Some(mut info) = self.playback_info.clone() {
-            info.status = status;
-            self.set_playback_info(info)?;
-        }
+    /// Resolves the artwork of everything already published again.
+    fn refresh_art_urls(&self) -> crate::Result<()> {
+        let (metadata, tracks) = {
+            let state = self.lock_state()?;
+            (state.metadata.clone(), state.tracks.clone())
+        };
+        let art_url = metadata.as_ref().and_then(|m| self.resolve_art_url(m));
+        let track_art_urls = self.resolve_track_art_urls(&tracks);
+
+        let mut state = self.lock_state()?;
+        state.art_url = art_url;
+        state.track_art_urls = track_art_urls;
         Ok(())
     }
 
-    fn set_position(&mut self, position: f64) -> Result<(), Box<dyn StdError>> {
-        if let Some(mut info) = self.playback_info.clone() {
-            info.position = position;
-            self.set_playback_info(info)?;
+    /// Trims the artwork cache, keeping every image that is still published.
+    fn prune_artwork(&self) -> crate::Result<()> {
+        let Some(artwork) = &self.artwork else {
+            return Ok(());
+        };
+        let in_use = {
+            let state = self.lock_state()?;
+            state
+                .art_url
+                .iter()
+                .chain(state.track_art_urls.values())
+                .cloned()
+                .collect::<HashSet<_>>()
+        };
+        artwork.prune(&in_use);
+        Ok(())
+    }
+
+    /// Announces the playback properties derived from `info`, followed by
+    /// `Seeked` when the position jumped.
+    #[cfg(target_os = "linux")]
+    fn emit_playback_info(&self, info: &PlaybackInfo, seeked: bool) {
+        let mut changed = PropMap::new();
+        changed.insert(
+            "PlaybackStatus".to_string(),
+            Variant(Box::new(playback_status_str(info.status).to_string()) as Box<dyn RefArg>),
+        );
+        changed.insert(
+            "Rate".to_string(),
+            Variant(Box::new(info.playback_rate) as Box<dyn RefArg>),
+        );
+        changed.insert(
+            "Shuffle".to_string(),
+            Variant(Box::new(info.shuffle) as Box<dyn RefArg>),
+        );
+        changed.insert(
+            "Volume".to_string(),
+            Variant(Box::new(info.volume) as Box<dyn RefArg>),
+        );
+        changed.insert(
+            "LoopStatus".to_string(),
+            Variant(Box::new(loop_status_str(info.repeat_mode).to_string()) as Box<dyn RefArg>),
+        );
+
+        self.emit_properties_changed("org.mpris.MediaPlayer2.Player", changed);
+        if seeked {
+            self.emit_seeked(info.position);
+        }
+    }
+
+    /// Tells clients that playback jumped to `position` seconds.
+    #[cfg(target_os = "linux")]
+    fn emit_seeked(&self, position: f64) {
+        if let Some(conn) = &self.connection {
+            let msg = dbus::Message::signal(
+                &dbus::Path::from("/org/mpris/MediaPlayer2"),
+                &"org.mpris.MediaPlayer2.Player".into(),
+                &"Seeked".into(),
+            )
+            .append1((position * 1_000_000.0) as i64);
+
+            let _ = conn.send(msg);
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    fn emit_signal(
+        &self,
+        interface: &'static str,
+        member: &'static str,
+        append: impl FnOnce(dbus::Message) -> dbus::Message,
+    ) {
+        if let Some(conn) = &self.connection {
+            let msg = dbus::Message::signal(
+                &dbus::Path::from("/org/mpris/MediaPlayer2"),
+                &interface.into(),
+                &member.into(),
+            );
+
+            let _ = conn.send(append(msg));
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    fn emit_track_list_signal(
+        &self,
+        member: &'static str,
+        append: impl FnOnce(dbus::Message) -> dbus::Message,
+    ) {
+        self.emit_signal("org.mpris.MediaPlayer2.TrackList", member, append);
+    }
+
+    #[cfg(target_os = "linux")]
+    fn emit_properties_changed(&self, interface: &str, changed: PropMap) {
+        if let Some(conn) = &self.connection {
+            let msg = dbus::Message::signal(
+                &dbus::Path::from("/org/mpris/MediaPlayer2"),
+                &"org.freedesktop.DBus.Properties".into(),
+                &"PropertiesChanged".into(),
+            )
+            .append3(interface, changed, Vec::<String>::new());
+
+            let _ = conn.send(msg);
+        }
+    }
+}
+
+/// Turns an arbitrary application id into the part of the bus name that
+/// follows `org.mpris.MediaPlayer2.`. Dots keep separating elements, every
+/// other character outside `[A-Za-z0-9_]` becomes `_`, and elements that
+/// would start with a digit are prefixed with `_`.
+#[cfg(target_os = "linux")]
+fn bus_name_suffix(app_id: &str) -> String {
+    let elements = app_id
+        .split('.')
+        .filter(|element| !element.is_empty())
+        .map(|element| {
+            let mut sanitized = String::with_capacity(element.len() + 1);
+            if element.starts_with(|c: char| c.is_ascii_digit()) {
+                sanitized.push('_');
+            }
+            sanitized.extend(element.chars().map(|c| {
+                if c.is_ascii_alphanumeric() || c == '_' {
+                    c
+                } else {
+                    '_'
+                }
+            }));
+            sanitized
+        })
+        .collect::<Vec<_>>();
+
+    if elements.is_empty() {
+        "app".to_string()
+    } else {
+        elements.join(".")
+    }
+}
+
+/// Opens a new connection to the session bus.
+#[cfg(target_os = "linux")]
+fn session_bus() -> crate::Result<SyncConnection> {
+    SyncConnection::new_session().map_err(|e| crate::Error::BusUnavailable { source: e.into() })
+}
+
+/// Claims `org.mpris.MediaPlayer2.<app_id>`, falling back to the
+/// `.instance<pid>` suffix the spec reserves for additional instances when
+/// the plain name is already owned. Additional sessions of the application
+/// always get an instance name that includes their session id, such as
+/// `.instance1234_preview`. Returns the name that was acquired.
+#[cfg(target_os = "linux")]
+fn request_bus_name(
+    conn: &SyncConnection,
+    app_id: &str,
+    instance: Option<&str>,
+) -> crate::Result<String> {
+    use dbus::blocking::stdintf::org_freedesktop_dbus::RequestNameReply;
+
+    let candidates = bus_name_candidates(app_id, std::process::id(), instance);
+    let taken = candidates[0].clone();
+    for name in candidates {
+        match conn.request_name(name.as_str(), false, false, true)? {
+            RequestNameReply::PrimaryOwner | RequestNameReply::AlreadyOwner => return Ok(name),
+            RequestNameReply::Exists | RequestNameReply::InQueue => {
+                log::debug!("MPRIS bus name {} is taken", name);
+            }
+        }
+    }
+
+    Err(crate::Error::NameTaken { name: taken })
+}
+
+/// The bus names [`request_bus_name`] tries, in order of preference.
+#[cfg(target_os = "linux")]
+fn bus_name_candidates(app_id: &str, pid: u32, instance: Option<&str>) -> Vec<String> {
+    // Bus names are limited to 255 characters, keep room for the longest
+    // instance suffix: `.instance`, a 10 digit pid, `_` and a 24 character id
+    let mut base = format!("org.mpris.MediaPlayer2.{}", bus_name_suffix(app_id));
+    base.truncate(210);
+    let base = base.trim_end_matches('.').to_string();
+
+    match instance {
+        None => vec![base.clone(), format!("{}.instance{}", base, pid)],
+        Some(id) => {
+            let id: String = id
+                .chars()
+                .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+                .take(24)
+                .collect();
+            vec![format!("{}.instance{}_{}", base, pid, id)]
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn lock_state(
+    state: &SharedState,
+) -> Result<std::sync::MutexGuard<'_, MprisState>, dbus::MethodErr> {
+    state
+        .lock()
+        .map_err(|_| dbus::MethodErr::failed("media session state is unavailable"))
+}
+
+fn playback_status_str(status: PlaybackStatus) -> &'static str {
+    match status {
+        PlaybackStatus::Playing => "Playing",
+        PlaybackStatus::Paused => "Paused",
+        PlaybackStatus::Stopped => "Stopped",
+    }
+}
+
+fn loop_status_str(repeat_mode: RepeatMode) -> &'static str {
+    match repeat_mode {
+        RepeatMode::None => "None",
+        RepeatMode::Track => "Track",
+        RepeatMode::List => "Playlist",
+    }
+}
+
+type CapabilityGetter = fn(&MediaCapabilities) -> bool;
+
+/// Player capability properties and how they derive from [`MediaCapabilities`].
+const CAPABILITY_PROPERTIES: [(&str, CapabilityGetter); 6] = [
+    ("CanGoNext", |c| c.can_go_next),
+    ("CanGoPrevious", |c| c.can_go_previous),
+    ("CanPlay", |c| c.can_play),
+    ("CanPause", |c| c.can_pause),
+    ("CanSeek", |c| c.can_seek),
+    ("CanControl", |c| c.can_control),
+];
+
+/// Value of a capability property. The spec requires every action to be
+/// reported as unavailable while `CanControl` is false.
+fn capability_enabled(capabilities: &MediaCapabilities, property: &str) -> bool {
+    CAPABILITY_PROPERTIES
+        .iter()
+        .find(|(name, _)| *name == property)
+        .is_some_and(|(_, get)| capabilities.can_control && get(capabilities))
+}
+
+/// Whether `uri` is absolute and uses one of the advertised `schemes`.
+/// Schemes are compared case-insensitively, as RFC 3986 requires.
+fn uri_scheme_supported(uri: &str, schemes: &[String]) -> bool {
+    let Some((scheme, _)) = uri.split_once(':') else {
+        return false;
+    };
+
+    let valid = scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+        && scheme
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+    valid && schemes.iter().any(|s| s.eq_ignore_ascii_case(scheme))
+}
+
+/// Maps an application-chosen id to an object path below `prefix`.
+/// Characters that are not valid in a path element are escaped as `_xx`
+/// hex pairs so the mapping stays stable and reversible.
+fn id_to_path(prefix: &str, id: &str) -> dbus::Path<'static> {
+    let mut path = format!("{}/", prefix);
+    for byte in id.bytes() {
+        if byte.is_ascii_alphanumeric() {
+            path.push(byte as char);
+        } else {
+            path.push_str(&format!("_{:02x}", byte));
         }
+    }
+    if id.is_empty() {
+        path.push('_');
+    }
+    dbus::Path::from(path)
+}
+
+/// Reverses [`id_to_path`]. Returns `None` for paths outside `prefix`.
+fn id_from_path(prefix: &str, path: &str) -> Option<String> {
+    let encoded = path.strip_prefix(prefix)?.strip_prefix('/')?;
+    if encoded == "_" {
+        return Some(String::new());
+    }
+
+    let mut bytes = Vec::with_capacity(encoded.len());
+    let mut chars = encoded.bytes();
+    while let Some(byte) = chars.next() {
+        if byte == b'_' {
+            let hex = [chars.next()?, chars.next()?];
+            let hex = std::str::from_utf8(&hex).ok()?;
+            bytes.push(u8::from_str_radix(hex, 16).ok()?);
+        } else {
+            bytes.push(byte);
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
+
+/// Object path advertised over MPRIS for an application track id.
+fn track_path(track_id: Option<&str>) -> dbus::Path<'static> {
+    match track_id {
+        Some(track_id) => id_to_path(TRACK_PATH_PREFIX, track_id),
+        None => dbus::Path::from(CURRENT_TRACK_PATH),
+    }
+}
+
+/// Reverses [`track_path`]. Returns `None` for paths that do not belong to a
+/// published track, including the spec's `NoTrack` path.
+fn track_id_from_path(path: &str) -> Option<String> {
+    id_from_path(TRACK_PATH_PREFIX, path)
+}
+
+/// The `(oss)` struct MPRIS uses to describe a playlist.
+fn playlist_struct(playlist: &Playlist) -> (dbus::Path<'static>, String, String) {
+    (
+        id_to_path(PLAYLIST_PATH_PREFIX, &playlist.id),
+        playlist.name.clone(),
+        playlist.icon.clone().unwrap_or_default(),
+    )
+}
+
+/// The `(b(oss))` value of the `ActivePlaylist` property.
+fn active_playlist_struct(state: &MprisState) -> (bool, (dbus::Path<'static>, String, String)) {
+    let active = state
+        .active_playlist_id
+        .as_ref()
+        .and_then(|id| state.playlists.iter().find(|p| &p.id == id));
+
+    match active {
+        Some(playlist) => (true, playlist_struct(playlist)),
+        None => (false, (dbus::Path::from("/"), String::new(), String::new())),
+    }
+}
+
+/// Resolves a track path received from a client to the id of a track in the
+/// published list.
+fn known_track_id(state: &MprisState, path: &str) -> Option<String> {
+    track_id_from_path(path).filter(|id| state.tracks.iter().any(|t| &t.id == id))
+}
+
+/// Hands an incoming control event to the registered handler, if any.
+fn dispatch_event(handler: &SharedEventHandler, event_type: MediaControlEventType) {
+    if let Ok(handler) = handler.lock() {
+        if let Some(handler) = handler.as_ref() {
+            handler(MediaControlEvent::new(event_type));
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn create_metadata_dict(
+    metadata: Option<&MediaMetadata>,
+    track_id: Option<&str>,
+    art_url: Option<&str>,
+) -> PropMap {
+    let mut dict = PropMap::new();
+
+    if let Some(meta) = metadata {
+        dict.insert(
+            "mpris:trackid".to_string(),
+            Variant(Box::new(track_path(track_id)) as Box<dyn RefArg>),
+        );
+
+        dict.insert(
+            "xesam:title".to_string(),
+            Variant(Box::new(meta.title.clone()) as Box<dyn RefArg>),
+        );
+
+        if let Some(album) = &meta.album {
+            dict.insert(
+                "xesam:album".to_string(),
+                Variant(Box::new(album.clone()) as Box<dyn RefArg>),
+            );
+        }
+
+        // List-valued xesam fields are only sent when they have entries
+        for (key, values) in [
+            ("xesam:artist", meta.all_artists()),
+            ("xesam:albumArtist", meta.all_album_artists()),
+            ("xesam:genre", meta.genres.clone()),
+            ("xesam:composer", meta.composers.clone()),
+            ("xesam:lyricist", meta.lyricists.clone()),
+            ("xesam:comment", meta.comment.iter().cloned().collect()),
+        ] {
+            if !values.is_empty() {
+                dict.insert(
+                    key.to_string(),
+                    Variant(Box::new(values) as Box<dyn RefArg>),
+                );
+            }
+        }
+
+        for (key, value) in [
+            ("xesam:trackNumber", meta.track_number),
+            ("xesam:discNumber", meta.disc_number),
+            ("xesam:useCount", meta.play_count),
+        ] {
+            if let Some(value) = value {
+                dict.insert(
+                    key.to_string(),
+                    Variant(Box::new(value.min(i32::MAX as u32) as i32) as Box<dyn RefArg>),
+                );
+            }
+        }
+
+        for (key, value) in [
+            ("xesam:contentCreated", &meta.release_date),
+            ("xesam:url", &meta.url),
+        ] {
+            if let Some(value) = value {
+                dict.insert(
+                    key.to_string(),
+                    Variant(Box::new(value.clone()) as Box<dyn RefArg>),
+                );
+            }
+        }
+
+        if let Some(rating) = meta.user_rating {
+            dict.insert(
+                "xesam:userRating".to_string(),
+                Variant(Box::new(rating.clamp(0.0, 1.0)) as Box<dyn RefArg>),
+            );
+        }
+
+        if let Some(duration) = meta.duration {
+            dict.insert(
+                "mpris:length".to_string(),
+                Variant(Box::new((duration * 1_000_000.0) as i64) as Box<dyn RefArg>),
+            );
+        }
+
+        // MPRIS only takes artwork as a URL, see `resolve_art_url`
+        if let Some(art_url) = art_url {
+            dict.insert(
+                "mpris:artUrl".to_string(),
+                Variant(Box::new(art_url.to_string()) as Box<dyn RefArg>),
+            );
+        }
+    }
+
+    dict
+}
+
+/// Reads a string entry, accepting object paths too since some players
+/// send `xesam:url` that way.
+#[cfg(target_os = "linux")]
+fn prop_str(dict: &PropMap, key: &str) -> Option<String> {
+    dict.get(key)
+        .and_then(|value| value.0.as_str())
+        .map(str::to_string)
+}
+
+/// Reads a string list entry. A plain string is treated as a one-element
+/// list, which some players send for `xesam:artist` and `xesam:genre`.
+#[cfg(target_os = "linux")]
+fn prop_strings(dict: &PropMap, key: &str) -> Vec<String> {
+    let Some(value) = dict.get(key) else {
+        return Vec::new();
+    };
+
+    match value.0.as_iter() {
+        Some(items) => items
+            .filter_map(|item| item.as_str().map(str::to_string))
+            .collect(),
+        None => value.0.as_str().map(str::to_string).into_iter().collect(),
+    }
+}
+
+/// Reads an integer entry regardless of the exact integer type the player used.
+#[cfg(target_os = "linux")]
+fn prop_u32(dict: &PropMap, key: &str) -> Option<u32> {
+    dict.get(key)
+        .and_then(|value| value.0.as_i64())
+        .and_then(|value| u32::try_from(value).ok())
+}
+
+/// Builds metadata from a remote player's `Metadata` dictionary. Returns
+/// `None` when the player publishes neither a title nor an artist.
+#[cfg(target_os = "linux")]
+fn parse_metadata_dict(dict: &PropMap) -> Option<MediaMetadata> {
+    let title = prop_str(dict, "xesam:title");
+    let artists = prop_strings(dict, "xesam:artist");
+    if title.is_none() && artists.is_empty() {
+        return None;
+    }
+
+    let album_artists = prop_strings(dict, "xesam:albumArtist");
+    let duration = dict
+        .get("mpris:length")
+        .and_then(|value| value.0.as_i64())
+        .map(|length| length as f64 / 1_000_000.0);
+
+    Some(MediaMetadata {
+        title: title.unwrap_or_else(|| "Unknown".to_string()),
+        artist: artists.first().cloned(),
+        album: prop_str(dict, "xesam:album"),
+        album_artist: album_artists.first().cloned(),
+        duration,
+        artwork_url: prop_str(dict, "mpris:artUrl"),
+        artwork_data: None, // MPRIS doesn't provide raw data
+        artists,
+        album_artists,
+        genres: prop_strings(dict, "xesam:genre"),
+        track_number: prop_u32(dict, "xesam:trackNumber"),
+        disc_number: prop_u32(dict, "xesam:discNumber"),
+        composers: prop_strings(dict, "xesam:composer"),
+        lyricists: prop_strings(dict, "xesam:lyricist"),
+        release_date: prop_str(dict, "xesam:contentCreated"),
+        url: prop_str(dict, "xesam:url"),
+        comment: {
+            let comment = prop_strings(dict, "xesam:comment");
+            (!comment.is_empty()).then(|| comment.join("\n"))
+        },
+        user_rating: dict
+            .get("xesam:userRating")
+            .and_then(|value| value.0.as_f64()),
+        play_count: prop_u32(dict, "xesam:useCount"),
+        ..Default::default()
+    })
+}
+
+/// Timeout for calls made to other players on the bus.
+#[cfg(target_os = "linux")]
+const REMOTE_CALL_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Bus names of every MPRIS player currently on the session bus.
+#[cfg(target_os = "linux")]
+fn list_player_names(conn: &SyncConnection) -> Result<Vec<String>, dbus::Error> {
+    let proxy = conn.with_proxy(
+        "org.freedesktop.DBus",
+        "/org/freedesktop/DBus",
+        REMOTE_CALL_TIMEOUT,
+    );
+    let (names,) =
+        proxy.method_call::<(Vec<String>,), _, _, _>("org.freedesktop.DBus", "ListNames", ())?;
+
+    Ok(names
+        .into_iter()
+        .filter(|name| name.starts_with("org.mpris.MediaPlayer2."))
+        .collect())
+}
+
+/// Whether `name` belongs to one of this application's own sessions.
+#[cfg(target_os = "linux")]
+fn owned_by_this_process(conn: &SyncConnection, name: &str) -> bool {
+    let proxy = conn.with_proxy(
+        "org.freedesktop.DBus",
+        "/org/freedesktop/DBus",
+        REMOTE_CALL_TIMEOUT,
+    );
+    proxy
+        .method_call::<(u32,), _, _, _>(
+            "org.freedesktop.DBus",
+            "GetConnectionUnixProcessID",
+            (name,),
+        )
+        .is_ok_and(|(pid,)| pid == std::process::id())
+}
+
+/// Maps the unique name owning each of `names` back to that name, so signals,
+/// which carry the unique sender, can be attributed to a player.
+#[cfg(target_os = "linux")]
+fn read_owners(conn: &SyncConnection, names: &[String]) -> HashMap<String, String> {
+    let proxy = conn.with_proxy(
+        "org.freedesktop.DBus",
+        "/org/freedesktop/DBus",
+        REMOTE_CALL_TIMEOUT,
+    );
+    names
+        .iter()
+        .filter_map(|name| {
+            let (owner,) = proxy
+                .method_call::<(String,), _, _, _>(
+                    "org.freedesktop.DBus",
+                    "GetNameOwner",
+                    (name.as_str(),),
+                )
+                .ok()?;
+            Some((owner, name.clone()))
+        })
+        .collect()
+}
+
+/// Reads a boolean entry; D-Bus booleans are exposed as integers by `RefArg`.
+#[cfg(target_os = "linux")]
+fn prop_bool(dict: &PropMap, key: &str) -> bool {
+    dict.get(key)
+        .and_then(|value| value.0.as_i64())
+        .is_some_and(|value| value != 0)
+}
+
+/// Snapshot of the player owning `name`. Returns `None` when the player does
+/// not answer, so one misbehaving player cannot hide the others.
+#[cfg(target_os = "linux")]
+fn read_session(conn: &SyncConnection, name: String) -> Option<SystemMediaSession> {
+    use dbus::blocking::stdintf::org_freedesktop_dbus::Properties;
+
+    let proxy = conn.with_proxy(
+        name.as_str(),
+        "/org/mpris/MediaPlayer2",
+        REMOTE_CALL_TIMEOUT,
+    );
+    let root = proxy.get_all("org.mpris.MediaPlayer2").ok()?;
+    let player = proxy.get_all("org.mpris.MediaPlayer2.Player").ok()?;
+    // Metadata is fetched on its own so it arrives as a typed dictionary
+    let metadata = proxy
+        .get::<PropMap>("org.mpris.MediaPlayer2.Player", "Metadata")
+        .ok()
+        .and_then(|dict| parse_metadata_dict(&dict));
+
+    let PlaybackInfo {
+        status, position, ..
+    } = read_playback_info(&player);
+
+    Some(SystemMediaSession {
+        identity: prop_str(&root, "Identity"),
+        desktop_entry: prop_str(&root, "DesktopEntry"),
+        status,
+        position,
+        metadata,
+        capabilities: read_capabilities(&player),
+        id: name,
+    })
+}
+
+/// Capability flags from a player's `org.mpris.MediaPlayer2.Player` properties.
+#[cfg(target_os = "linux")]
+fn read_capabilities(player: &PropMap) -> MediaCapabilities {
+    MediaCapabilities {
+        can_go_next: prop_bool(player, "CanGoNext"),
+        can_go_previous: prop_bool(player, "CanGoPrevious"),
+        can_play: prop_bool(player, "CanPlay"),
+        can_pause: prop_bool(player, "CanPause"),
+        can_seek: prop_bool(player, "CanSeek"),
+        can_control: prop_bool(player, "CanControl"),
+    }
+}
+
+/// Playback state from a player's `org.mpris.MediaPlayer2.Player` properties.
+#[cfg(target_os = "linux")]
+fn read_playback_info(player: &PropMap) -> PlaybackInfo {
+    let repeat_mode = match prop_str(player, "LoopStatus").as_deref() {
+        Some("Track") => RepeatMode::Track,
+        Some("Playlist") => RepeatMode::List,
+        _ => RepeatMode::None,
+    };
+    let prop_f64 = |key: &str| player.get(key).and_then(|value| value.0.as_f64());
+
+    PlaybackInfo {
+        status: parse_playback_status(prop_str(player, "PlaybackStatus").as_deref()),
+        position: player
+            .get("Position")
+            .and_then(|value| value.0.as_i64())
+            .map(|position| position as f64 / 1_000_000.0)
+            .unwrap_or(0.0),
+        shuffle: prop_bool(player, "Shuffle"),
+        repeat_mode,
+        playback_rate: prop_f64("Rate").unwrap_or(1.0),
+        volume: prop_f64("Volume").unwrap_or(1.0),
+    }
+}
+
+fn parse_playback_status(status: Option<&str>) -> PlaybackStatus {
+    match status {
+        Some("Playing") => PlaybackStatus::Playing,
+        Some("Paused") => PlaybackStatus::Paused,
+        _ => PlaybackStatus::Stopped,
+    }
+}
+
+/// Turns a `PropertiesChanged` signal from a player into a [`SessionChange`].
+/// Changed metadata and capabilities are re-read from the player so they
+/// arrive complete and typed. Returns `None` when nothing we report changed.
+#[cfg(target_os = "linux")]
+fn read_session_change(
+    conn: &SyncConnection,
+    id: String,
+    changed: &PropMap,
+    invalidated: &[String],
+) -> Option<SessionChange> {
+    use dbus::blocking::stdintf::org_freedesktop_dbus::Properties;
+
+    let touched = |key: &str| changed.contains_key(key) || invalidated.iter().any(|k| k == key);
+    let proxy = conn.with_proxy(id.as_str(), "/org/mpris/MediaPlayer2", REMOTE_CALL_TIMEOUT);
+
+    let mut change = SessionChange::default();
+    if touched("PlaybackStatus") {
+        change.status = Some(parse_playback_status(
+            prop_str(changed, "PlaybackStatus").as_deref(),
+        ));
+    }
+    if touched("Metadata") {
+        change.metadata = proxy
+            .get::<PropMap>("org.mpris.MediaPlayer2.Player", "Metadata")
+            .ok()
+            .and_then(|dict| parse_metadata_dict(&dict));
+    }
+    if CAPABILITY_PROPERTIES.iter().any(|(name, _)| touched(name)) {
+        change.capabilities = proxy
+            .get_all("org.mpris.MediaPlayer2.Player")
+            .ok()
+            .map(|player| read_capabilities(&player));
+    }
+
+    let empty =
+        change.status.is_none() && change.metadata.is_none() && change.capabilities.is_none();
+    change.id = id;
+    (!empty).then_some(change)
+}
+
+/// Forwards `event` to the watcher's handler.
+#[cfg(target_os = "linux")]
+fn emit_session_event(handler: &SessionEventHandler, event: SessionEvent) {
+    if let Ok(handler) = handler.lock() {
+        handler(event);
+    }
+}
+
+/// Well-known MPRIS name owned by the sender of `msg`, if any.
+#[cfg(target_os = "linux")]
+fn session_of(owners: &SessionOwners, msg: &dbus::Message) -> Option<String> {
+    let sender = msg.sender()?;
+    owners.lock().ok()?.get(&*sender).cloned()
+}
+
+impl super::MediaController for LinuxMediaController {
+    fn initialize_session(
+        &mut self,
+        request: InitializeMediaSessionRequest,
+    ) -> crate::Result<MediaSessionInfo> {
+        self.app_id = request.app_id;
+        self.app_name = request.app_name;
+        // Lists given with the session take precedence over the plugin config
+        if let Some(schemes) = request.supported_uri_schemes {
+            self.supported_uri_schemes = schemes;
+        }
+        if let Some(mime_types) = request.supported_mime_types {
+            self.supported_mime_types = mime_types;
+        }
+
+        #[cfg(target_os = "linux")]
+        {
+            self.setup_mpris()?;
+        }
+
+        Ok(MediaSessionInfo {
+            #[cfg(target_os = "linux")]
+            bus_name: self.bus_name.clone(),
+            #[cfg(not(target_os = "linux"))]
+            bus_name: None,
+        })
+    }
+
+    fn set_metadata(&mut self, metadata: MediaMetadata) -> crate::Result<()> {
+        let art_url = self.resolve_art_url(&metadata);
+        let dict = create_metadata_dict(
+            Some(&metadata),
+            metadata.track_id.as_deref(),
+            art_url.as_deref(),
+        );
+        {
+            let mut state = self.lock_state()?;
+            state.metadata = Some(metadata);
+            state.art_url = art_url;
+        }
+        self.prune_artwork()?;
+
+        #[cfg(target_os = "linux")]
+        {
+            // Send PropertiesChanged signal
+            let mut changed = PropMap::new();
+            changed.insert(
+                "Metadata".to_string(),
+                Variant(Box::new(dict) as Box<dyn RefArg>),
+            );
+            self.emit_properties_changed("org.mpris.MediaPlayer2.Player", changed);
+        }
+
         Ok(())
     }
 
-    fn clear_metadata(&mut self) -> Result<(), Box<dyn StdError>> {
-        self.metadata = None;
+    fn set_playback_info(&mut self, info: PlaybackInfo) -> crate::Result<()> {
+        let seeked = self
+            .lock_state()?
+            .report_playback_info(info.clone(), Instant::now());
+        #[cfg(target_os = "linux")]
+        self.emit_playback_info(&info, seeked);
+        Ok(())
+    }
+
+    fn set_playback_status(&mut self, status: PlaybackStatus) -> crate::Result<()> {
+        let update = self
+            .lock_state()?
+            .update_playback_info(Instant::now(), |info| info.status = status);
+        #[cfg(target_os = "linux")]
+        if let Some((info, seeked)) = update {
+            self.emit_playback_info(&info, seeked);
+        }
+        Ok(())
+    }
+
+    fn set_position(&mut self, position: f64) -> crate::Result<()> {
+        let update = self
+            .lock_state()?
+            .update_playback_info(Instant::now(), |info| info.position = position);
+        #[cfg(target_os = "linux")]
+        if let Some((info, seeked)) = update {
+            self.emit_playback_info(&info, seeked);
+        }
+        Ok(())
+    }
+
+    fn clear_metadata(&mut self) -> crate::Result<()> {
+        {
+            let mut state = self.lock_state()?;
+            state.metadata = None;
+            state.art_url = None;
+        }
 
         #[cfg(target_os = "linux")]
         {
             // Send empty metadata
-            if let Some(conn) = &self.connection {
-                let msg = dbus::Message::signal(
-                    &dbus::Path::from("/org/mpris/MediaPlayer2"),
-                    &"org.freedesktop.DBus.Properties".into(),
-                    &"PropertiesChanged".into(),
-                )
-                .append1("org.mpris.MediaPlayer2.Player")
-                .append2(
-                    vec![("Metadata", HashMap::new())]
-                        .into_iter()
-                        .collect::<HashMap<_, _>>(),
-                    Vec::<String>::new(),
-                );
+            let mut changed = PropMap::new();
+            changed.insert(
+                "Metadata".to_string(),
+                Variant(Box::new(PropMap::new()) as Box<dyn RefArg>),
+            );
+            self.emit_properties_changed("org.mpris.MediaPlayer2.Player", changed);
+        }
+
+        Ok(())
+    }
 
-                let _ = conn.send(msg);
+    fn set_event_handler(&mut self, handler: Box<dyn Fn(MediaControlEvent) + Send>) {
+        // The dispatch thread shares this slot, so a handler installed after
+        // initialize_session still receives events
+        if let Ok(mut slot) = self.event_handler.lock() {
+            *slot = Some(handler);
+        }
+    }
+
+    fn set_capabilities(&mut self, capabilities: MediaCapabilities) -> crate::Result<()> {
+        let previous = std::mem::replace(&mut self.lock_state()?.capabilities, capabilities);
+
+        #[cfg(target_os = "linux")]
+        {
+            // Only announce the properties whose reported value actually changed
+            let mut changed = PropMap::new();
+            for (property, _) in CAPABILITY_PROPERTIES {
+                let value = capability_enabled(&capabilities, property);
+                if value != capability_enabled(&previous, property) {
+                    changed.insert(
+                        property.to_string(),
+                        Variant(Box::new(value) as Box<dyn RefArg>),
+                    );
+                }
+            }
+            if !changed.is_empty() {
+                self.emit_properties_changed("org.mpris.MediaPlayer2.Player", changed);
             }
         }
 
         Ok(())
     }
 
-    fn set_event_handler(&mut self, handler: Box<dyn Fn(MediaControlEvent) + Send>) {
-        self.event_handler = Some(handler);
-        // Note: Actual DBus signal handling would be set up in setup_mpris
+    fn set_track_list(&mut self, tracks: Vec<Track>) -> crate::Result<()> {
+        let art_urls = self.resolve_track_art_urls(&tracks);
+        let (paths, current) = {
+            let mut state = self.lock_state()?;
+            state.tracks = tracks;
+            state.track_art_urls = art_urls;
+            let paths = state
+                .tracks
+                .iter()
+                .map(|t| track_path(Some(&t.id)))
+                .collect::<Vec<_>>();
+            (paths, state.current_track_path())
+        };
+
+        self.prune_artwork()?;
+
+        #[cfg(target_os = "linux")]
+        self.emit_track_list_signal("TrackListReplaced", |msg| msg.append2(paths, current));
+
+        Ok(())
+    }
+
+    fn add_track(&mut self, track: Track, after_track_id: Option<String>) -> crate::Result<()> {
+        let art_url = self.resolve_art_url(&track.metadata);
+        let dict = create_metadata_dict(Some(&track.metadata), Some(&track.id), art_url.as_deref());
+        let after = {
+            let mut state = self.lock_state()?;
+            let track_id = track.id.clone();
+            match art_url {
+                Some(art_url) => state.track_art_urls.insert(track_id.clone(), art_url),
+                None => state.track_art_urls.remove(&track_id),
+            };
+            super::insert_track(&mut state.tracks, track, after_track_id.as_deref());
+
+            // Report the actual predecessor, which differs from the requested
+            // one when that id is unknown
+            let index = state.tracks.iter().position(|t| t.id == track_id);
+            match index.and_then(|i| i.checked_sub(1)) {
+                Some(i) => track_path(Some(&state.tracks[i].id)),
+                None => dbus::Path::from(NO_TRACK_PATH),
+            }
+        };
+
+        self.prune_artwork()?;
+
+        #[cfg(target_os = "linux")]
+        self.emit_track_list_signal("TrackAdded", |msg| msg.append2(dict, after));
+
+        Ok(())
     }
 
-    fn get_metadata(&self) -> Result<Option<MediaMetadata>, Box<dyn StdError>> {
-        // Linux'ta DBus üzerinden diğer media player'lardan bilgi almak için
-        // org.mpris.MediaPlayer2.* servislerini sorgulamamız gerekiyor
+    fn remove_track(&mut self, track_id: String) -> crate::Result<()> {
+        let removed = {
+            let mut state = self.lock_state()?;
+            let len = state.tracks.len();
+            state.tracks.retain(|t| t.id != track_id);
+            state.track_art_urls.remove(&track_id);
+            state.tracks.len() != len
+        };
+
+        #[cfg(target_os = "linux")]
+        if removed {
+            self.emit_track_list_signal("TrackRemoved", |msg| {
+                msg.append1(track_path(Some(&track_id)))
+            });
+        }
+
+        Ok(())
+    }
+
+    fn update_track_metadata(
+        &mut self,
+        track_id: String,
+        metadata: MediaMetadata,
+    ) -> crate::Result<()> {
+        let art_url = self.resolve_art_url(&metadata);
+        let dict = create_metadata_dict(Some(&metadata), Some(&track_id), art_url.as_deref());
+        let updated = {
+            let mut state = self.lock_state()?;
+            let updated = super::update_track_metadata(&mut state.tracks, &track_id, metadata);
+            if updated {
+                match art_url {
+                    Some(art_url) => state.track_art_urls.insert(track_id.clone(), art_url),
+                    None => state.track_art_urls.remove(&track_id),
+                };
+            }
+            updated
+        };
+        self.prune_artwork()?;
+
+        #[cfg(target_os = "linux")]
+        if updated {
+            self.emit_track_list_signal("TrackMetadataChanged", |msg| {
+                msg.append2(track_path(Some(&track_id)), dict)
+            });
+        }
+
+        Ok(())
+    }
+
+    fn set_playlists(
+        &mut self,
+        playlists: Vec<Playlist>,
+        active_playlist_id: Option<String>,
+    ) -> crate::Result<()> {
+        let (changed_playlists, count, active) = {
+            let mut state = self.lock_state()?;
+
+            // Existing playlists whose name or icon changed get a PlaylistChanged signal
+            let changed_playlists = playlists
+                .iter()
+                .filter(|p| {
+                    state
+                        .playlists
+                        .iter()
+                        .any(|old| old.id == p.id && (old.name != p.name || old.icon != p.icon))
+                })
+                .map(playlist_struct)
+                .collect::<Vec<_>>();
+
+            state.playlists = playlists;
+            state.active_playlist_id = active_playlist_id;
+            (
+                changed_playlists,
+                state.playlists.len() as u32,
+                active_playlist_struct(&state),
+            )
+        };
+
         #[cfg(target_os = "linux")]
         {
-            if let Some(conn) = &self.connection {
-                // List all MPRIS players
-                let proxy = conn.with_proxy(
-                    "org.freedesktop.DBus",
-                    "/",
-                    std::time::Duration::from_millis(500),
+            for playlist in changed_playlists {
+                self.emit_signal(
+                    "org.mpris.MediaPlayer2.Playlists",
+                    "PlaylistChanged",
+                    |msg| msg.append1(playlist),
                 );
-                use dbus::blocking::stdintf::org_freedesktop_dbus::Peer;
+            }
 
-                if let Ok(names) = proxy.list_names() {
-                    for name in names {
-                        if name.starts_with("org.mpris.MediaPlayer2.")
-                            && !name.contains(&self.app_id)
-                        {
-                            // Found another media player, try to get its metadata
-                            let player_proxy = conn.with_proxy(
-                                &name,
-                                "/org/mpris/MediaPlayer2",
-                                std::time::Duration::from_millis(500),
-                            );
+            let mut changed = PropMap::new();
+            changed.insert(
+                "PlaylistCount".to_string(),
+                Variant(Box::new(count) as Box<dyn RefArg>),
+            );
+            changed.insert(
+                "ActivePlaylist".to_string(),
+                Variant(Box::new(active) as Box<dyn RefArg>),
+            );
+            self.emit_properties_changed("org.mpris.MediaPlayer2.Playlists", changed);
+        }
+
+        Ok(())
+    }
+
+    fn list_sessions(&self) -> crate::Result<Vec<SystemMediaSession>> {
+        let conn = self.bus_connection()?;
+        // Players in policy order, then the ones the policy leaves out
+        let mut names = self.ranked_players(&conn)?;
+        for name in list_player_names(&conn)? {
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
 
-                            use dbus::blocking::stdintf::org_freedesktop_dbus::Properties;
-                            if let Ok(metadata_variant) =
-                                player_proxy.get("org.mpris.MediaPlayer2.Player", "Metadata")
-                            {
-                                if let Ok(metadata) = metadata_variant.0.as_iter() {
-                                    let mut title = None;
-                                    let mut artist = None;
-                                    let mut album = None;
-                                    let mut artwork_url = None;
-
-                                    for (key, value) in metadata {
-                                        if let Some(key_str) = key.as_str() {
-                                            match key_str {
-                                                "xesam:title" => {
-                                                    if let Some(v) = value.as_str() {
-                                                        title = Some(v.to_string());
-                                                    }
-                                                }
-                                                "xesam:artist" => {
-                                                    if let Some(arr) = value.as_iter() {
-                                                        if let Some(first) = arr.next() {
-                                                            if let Some(v) = first.1.as_str() {
-                                                                artist = Some(v.to_string());
-                                                            }
-                                                        }
-                                                    }
-                                                }
-                                                "xesam:album" => {
-                                                    if let Some(v) = value.as_str() {
-                                                        album = Some(v.to_string());
-                                                    }
-                                                }
-                                                "mpris:artUrl" => {
-                                                    if let Some(v) = value.as_str() {
-                                                        artwork_url = Some(v.to_string());
-                                                    }
-                                                }
-                                                _ => {}
-                                            }
-                                        }
-                                    }
-
-                                    if title.is_some() || artist.is_some() {
-                                        return Ok(Some(MediaMetadata {
-                                            title: title.unwrap_or_else(|| "Unknown".to_string()),
-                                            artist,
-                                            album,
-                                            album_artist: None,
-                                            artwork_url,
-                                            artwork_data: None, // MPRIS doesn't provide raw data
-                                            duration: None,
-                                        }));
-                                    }
-                                }
+        Ok(names
+            .into_iter()
+            .filter_map(|name| read_session(&conn, name))
+            .collect())
+    }
+
+    fn control_session(
+        &self,
+        session_id: Option<String>,
+        action: SessionAction,
+    ) -> crate::Result<()> {
+        use dbus::blocking::stdintf::org_freedesktop_dbus::Properties;
+
+        let conn = self.bus_connection()?;
+        let session_id = match session_id {
+            Some(id) if list_player_names(&conn)?.contains(&id) => id,
+            Some(id) => return Err(crate::Error::SessionNotFound(id)),
+            None => match self.ranked_players(&conn)?.into_iter().next() {
+                Some(id) => id,
+                None => return Err(crate::Error::NoActiveSession),
+            },
+        };
+
+        let proxy = conn.with_proxy(
+            session_id.as_str(),
+            "/org/mpris/MediaPlayer2",
+            REMOTE_CALL_TIMEOUT,
+        );
+        let player = proxy.get_all("org.mpris.MediaPlayer2.Player")?;
+
+        // Refuse up front instead of relying on players to report misuse,
+        // since the spec lets them silently ignore unsupported calls
+        let capability = match action {
+            SessionAction::Play => "CanPlay",
+            SessionAction::Pause | SessionAction::PlayPause => "CanPause",
+            SessionAction::Next => "CanGoNext",
+            SessionAction::Previous => "CanGoPrevious",
+            SessionAction::SeekBy(_) | SessionAction::SetPosition(_) => "CanSeek",
+            SessionAction::Stop
+            | SessionAction::SetVolume(_)
+            | SessionAction::SetShuffle(_)
+            | SessionAction::SetRepeatMode(_) => "CanControl",
+        };
+        if !prop_bool(&player, "CanControl") || !prop_bool(&player, capability) {
+            return Err(crate::Error::Unsupported {
+                feature: format!("{} on {}", capability, session_id),
+            });
+        }
+
+        const PLAYER: &str = "org.mpris.MediaPlayer2.Player";
+        match action {
+            SessionAction::Play => proxy.method_call(PLAYER, "Play", ())?,
+            SessionAction::Pause => proxy.method_call(PLAYER, "Pause", ())?,
+            SessionAction::PlayPause => proxy.method_call(PLAYER, "PlayPause", ())?,
+            SessionAction::Stop => proxy.method_call(PLAYER, "Stop", ())?,
+            SessionAction::Next => proxy.method_call(PLAYER, "Next", ())?,
+            SessionAction::Previous => proxy.method_call(PLAYER, "Previous", ())?,
+            SessionAction::SeekBy(offset) => {
+                proxy.method_call(PLAYER, "Seek", ((offset * 1_000_000.0) as i64,))?
+            }
+            SessionAction::SetPosition(position) => {
+                // SetPosition has to name the current track, which the player reports
+                let metadata = proxy.get::<PropMap>(PLAYER, "Metadata")?;
+                let track_id = prop_str(&metadata, "mpris:trackid")
+                    .and_then(|path| dbus::Path::new(path).ok())
+                    .unwrap_or_else(|| dbus::Path::from(NO_TRACK_PATH));
+                proxy.method_call(
+                    PLAYER,
+                    "SetPosition",
+                    (track_id, (position.max(0.0) * 1_000_000.0) as i64),
+                )?
+            }
+            SessionAction::SetVolume(volume) => proxy.set(PLAYER, "Volume", volume.max(0.0))?,
+            SessionAction::SetShuffle(shuffle) => proxy.set(PLAYER, "Shuffle", shuffle)?,
+            SessionAction::SetRepeatMode(repeat_mode) => proxy.set(
+                PLAYER,
+                "LoopStatus",
+                loop_status_str(repeat_mode).to_string(),
+            )?,
+        }
+
+        Ok(())
+    }
+
+    fn watch_sessions(&mut self, handler: Box<dyn Fn(SessionEvent) + Send>) -> crate::Result<()> {
+        // Watching again replaces the previous handler
+        self.unwatch_sessions();
+
+        #[cfg(target_os = "linux")]
+        {
+            // The watcher has its own connection so it neither depends on nor
+            // competes with the dispatch loop of our own session
+            let conn = Arc::new(session_bus()?);
+            let handler: SessionEventHandler = Arc::new(Mutex::new(handler));
+            let names = list_player_names(&conn)?;
+            let owners: SessionOwners = Arc::new(Mutex::new(read_owners(&conn, &names)));
+
+            conn.add_match(
+                MatchRule::new_signal("org.freedesktop.DBus", "NameOwnerChanged")
+                    .with_sender("org.freedesktop.DBus"),
+                {
+                    let handler = handler.clone();
+                    let owners = owners.clone();
+                    move |(name, old_owner, new_owner): (String, String, String),
+                          conn: &SyncConnection,
+                          _: &dbus::Message| {
+                        if !name.starts_with("org.mpris.MediaPlayer2.") {
+                            return true;
+                        }
+
+                        if !old_owner.is_empty() {
+                            if let Ok(mut owners) = owners.lock() {
+                                owners.remove(&old_owner);
+                            }
+                            emit_session_event(&handler, SessionEvent::Removed(name.clone()));
+                        }
+                        if !new_owner.is_empty() {
+                            if let Ok(mut owners) = owners.lock() {
+                                owners.insert(new_owner, name.clone());
+                            }
+                            if let Some(session) = read_session(conn, name) {
+                                emit_session_event(&handler, SessionEvent::Added(session));
                             }
                         }
+                        true
                     }
-                }
+                },
+            )?;
+
+            conn.add_match(
+                MatchRule::new_signal("org.freedesktop.DBus.Properties", "PropertiesChanged")
+                    .with_path("/org/mpris/MediaPlayer2"),
+                {
+                    let handler = handler.clone();
+                    let owners = owners.clone();
+                    move |(interface, changed, invalidated): (String, PropMap, Vec<String>),
+                          conn: &SyncConnection,
+                          msg: &dbus::Message| {
+                        if interface != "org.mpris.MediaPlayer2.Player" {
+                            return true;
+                        }
+                        let Some(id) = session_of(&owners, msg) else {
+                            return true;
+                        };
+
+                        if let Some(change) = read_session_change(conn, id, &changed, &invalidated)
+                        {
+                            emit_session_event(&handler, SessionEvent::Changed(change));
+                        }
+                        true
+                    }
+                },
+            )?;
+
+            conn.add_match(
+                MatchRule::new_signal("org.mpris.MediaPlayer2.Player", "Seeked")
+                    .with_path("/org/mpris/MediaPlayer2"),
+                move |(position,): (i64,), _: &SyncConnection, msg: &dbus::Message| {
+                    if let Some(id) = session_of(&owners, msg) {
+                        let change = SessionChange {
+                            id,
+                            position: Some(position as f64 / 1_000_000.0),
+                            ..Default::default()
+                        };
+                        emit_session_event(&handler, SessionEvent::Changed(change));
+                    }
+                    true
+                },
+            )?;
+
+            let running = Arc::new(AtomicBool::new(true));
+            self.watch_running = running.clone();
+
+            let thread = std::thread::Builder::new()
+                .name("mpris-watch".to_string())
+                .spawn(move || {
+                    while running.load(Ordering::Acquire) {
+                        if let Err(e) = conn.process(DISPATCH_INTERVAL) {
+                            log::warn!("MPRIS session watcher stopped: {}", e);
+                            break;
+                        }
+                    }
+                })?;
+            self.watch_thread = Some(thread);
+        }
+
+        Ok(())
+    }
+
+    fn unwatch_sessions(&mut self) {
+        #[cfg(target_os = "linux")]
+        {
+            self.watch_running.store(false, Ordering::Release);
+            if let Some(thread) = self.watch_thread.take() {
+                let _ = thread.join();
             }
         }
+    }
 
-        // Fall back to our own metadata
-        Ok(self.metadata.clone())
+    fn get_metadata(&self) -> crate::Result<Option<MediaMetadata>> {
+        Ok(self.lock_state()?.metadata.clone())
     }
 
-    fn get_playback_info(&self) -> Result<Option<PlaybackInfo>, Box<dyn StdError>> {
-        Ok(self.playback_info.clone())
+    fn get_playback_info(&self) -> crate::Result<Option<PlaybackInfo>> {
+        Ok(self.lock_state()?.playback_info.clone())
     }
 
-    fn get_playback_status(&self) -> Result<PlaybackStatus, Box<dyn StdError>> {
+    fn get_playback_status(&self) -> crate::Result<PlaybackStatus> {
         Ok(self
+            .lock_state()?
             .playback_info
             .as_ref()
             .map(|info| info.status)
             .unwrap_or(PlaybackStatus::Stopped))
     }
 
-    fn get_position(&self) -> Result<f64, Box<dyn StdError>> {
+    fn get_position(&self) -> crate::Result<f64> {
         Ok(self
+            .lock_state()?
             .playback_info
             .as_ref()
             .map(|info| info.position)
             .unwrap_or(0.0))
     }
 
-    fn is_enabled(&self) -> Result<bool, Box<dyn StdError>> {
+    fn is_enabled(&self) -> crate::Result<bool> {
         Ok(self.connection.is_some())
     }
+
+    fn get_track_list(&self) -> crate::Result<Vec<Track>> {
+        Ok(self.lock_state()?.tracks.clone())
+    }
+
+    fn get_system_metadata(&self) -> crate::Result<Option<MediaMetadata>> {
+        use dbus::blocking::stdintf::org_freedesktop_dbus::Properties;
+
+        let Some((conn, name)) = self.active_player()? else {
+            return Ok(None);
+        };
+        let proxy = conn.with_proxy(
+            name.as_str(),
+            "/org/mpris/MediaPlayer2",
+            REMOTE_CALL_TIMEOUT,
+        );
+        let metadata = proxy.get::<PropMap>("org.mpris.MediaPlayer2.Player", "Metadata")?;
+        Ok(parse_metadata_dict(&metadata))
+    }
+
+    fn get_system_playback_info(&self) -> crate::Result<Option<PlaybackInfo>> {
+        use dbus::blocking::stdintf::org_freedesktop_dbus::Properties;
+
+        let Some((conn, name)) = self.active_player()? else {
+            return Ok(None);
+        };
+        let proxy = conn.with_proxy(
+            name.as_str(),
+            "/org/mpris/MediaPlayer2",
+            REMOTE_CALL_TIMEOUT,
+        );
+        let player = proxy.get_all("org.mpris.MediaPlayer2.Player")?;
+        Ok(Some(read_playback_info(&player)))
+    }
+
+    fn get_system_playback_status(&self) -> crate::Result<PlaybackStatus> {
+        Ok(self
+            .get_system_playback_info()?
+            .map(|info| info.status)
+            .unwrap_or(PlaybackStatus::Stopped))
+    }
+
+    fn get_system_position(&self) -> crate::Result<f64> {
+        Ok(self
+            .get_system_playback_info()?
+            .map(|info| info.position)
+            .unwrap_or(0.0))
+    }
+
+    fn end_session(&mut self) -> crate::Result<()> {
+        #[cfg(target_os = "linux")]
+        if self.connection.is_some() {
+            // Announced before the name goes, for clients that cache state
+            self.set_playback_status(PlaybackStatus::Stopped)?;
+            self.clear_metadata()?;
+            self.teardown_mpris();
+        }
+        *self.lock_state()? = MprisState::default();
+        Ok(())
+    }
+}
+
+impl Drop for LinuxMediaController {
+    fn drop(&mut self) {
+        super::MediaController::unwatch_sessions(self);
+        #[cfg(target_os = "linux")]
+        {
+            self.teardown_mpris();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(id: &str) -> Option<String> {
+        id_from_path(TRACK_PATH_PREFIX, &id_to_path(TRACK_PATH_PREFIX, id))
+    }
+
+    #[test]
+    fn ids_round_trip_through_object_paths() {
+        for id in [
+            "track1",
+            "_",
+            "__",
+            "a_b",
+            "a/b.c-d",
+            "ünïcödé",
+            "曲",
+            " ",
+            "_5f",
+        ] {
+            assert_eq!(round_trip(id).as_deref(), Some(id), "id {:?}", id);
+        }
+    }
+
+    #[test]
+    fn empty_id_gets_a_valid_path() {
+        let path = id_to_path(TRACK_PATH_PREFIX, "");
+        assert_eq!(&*path, "/com/tauri/media/Track/_");
+        assert_eq!(round_trip("").as_deref(), Some(""));
+    }
+
+    #[test]
+    fn escaped_ids_only_use_valid_path_characters() {
+        let path = id_to_path(TRACK_PATH_PREFIX, "a_b/ü");
+        assert_eq!(&*path, "/com/tauri/media/Track/a_5fb_2f_c3_bc");
+    }
+
+    #[test]
+    fn paths_outside_the_prefix_are_rejected() {
+        assert_eq!(id_from_path(TRACK_PATH_PREFIX, NO_TRACK_PATH), None);
+        assert_eq!(
+            id_from_path(TRACK_PATH_PREFIX, "/com/tauri/media/TrackX/a"),
+            None
+        );
+        assert_eq!(id_from_path(TRACK_PATH_PREFIX, TRACK_PATH_PREFIX), None);
+        assert_eq!(
+            id_from_path(PLAYLIST_PATH_PREFIX, "/com/tauri/media/Track/a"),
+            None
+        );
+    }
+
+    #[test]
+    fn malformed_escapes_are_rejected() {
+        assert_eq!(
+            id_from_path(TRACK_PATH_PREFIX, "/com/tauri/media/Track/a_"),
+            None
+        );
+        assert_eq!(
+            id_from_path(TRACK_PATH_PREFIX, "/com/tauri/media/Track/a_zz"),
+            None
+        );
+        // A lone continuation byte is not valid UTF-8
+        assert_eq!(
+            id_from_path(TRACK_PATH_PREFIX, "/com/tauri/media/Track/_bc"),
+            None
+        );
+    }
+
+    #[test]
+    fn track_paths_distinguish_listed_and_unlisted_tracks() {
+        assert_eq!(&*track_path(None), CURRENT_TRACK_PATH);
+        assert_eq!(track_id_from_path(CURRENT_TRACK_PATH), None);
+        assert_eq!(
+            track_id_from_path(&track_path(Some("t1"))).as_deref(),
+            Some("t1")
+        );
+    }
+
+    #[test]
+    fn bus_name_elements_never_start_with_a_digit() {
+        assert_eq!(bus_name_suffix("com.example.app"), "com.example.app");
+        assert_eq!(bus_name_suffix("3d.viewer"), "_3d.viewer");
+        assert_eq!(bus_name_suffix("com.2048.game"), "com._2048.game");
+    }
+
+    #[test]
+    fn empty_bus_name_elements_are_dropped() {
+        assert_eq!(bus_name_suffix("com..example."), "com.example");
+        assert_eq!(bus_name_suffix(".app"), "app");
+        assert_eq!(bus_name_suffix(""), "app");
+        assert_eq!(bus_name_suffix("..."), "app");
+    }
+
+    #[test]
+    fn invalid_bus_name_characters_are_replaced() {
+        assert_eq!(bus_name_suffix("my-app.tauri"), "my_app.tauri");
+        assert_eq!(bus_name_suffix("Müsic Player"), "M_sic_Player");
+    }
+
+    #[test]
+    fn default_session_falls_back_to_an_instance_name() {
+        assert_eq!(
+            bus_name_candidates("com.example.app", 42, None),
+            [
+                "org.mpris.MediaPlayer2.com.example.app",
+                "org.mpris.MediaPlayer2.com.example.app.instance42",
+            ]
+        );
+        assert_eq!(
+            bus_name_candidates("1app", 42, None),
+            [
+                "org.mpris.MediaPlayer2._1app",
+                "org.mpris.MediaPlayer2._1app.instance42",
+            ]
+        );
+    }
+
+    #[test]
+    fn additional_sessions_only_use_their_instance_name() {
+        assert_eq!(
+            bus_name_candidates("com.example.app", 42, Some("mini-player")),
+            ["org.mpris.MediaPlayer2.com.example.app.instance42_mini_player"]
+        );
+        assert_eq!(
+            bus_name_candidates("", 7, Some("")),
+            ["org.mpris.MediaPlayer2.app.instance7_"]
+        );
+    }
+
+    #[test]
+    fn long_application_ids_keep_room_for_the_instance_suffix() {
+        let app_id = format!("{}.b", "a".repeat(300));
+        for name in bus_name_candidates(&app_id, u32::MAX, Some(&"x".repeat(100))) {
+            assert!(name.len() <= 255, "{} is too long", name);
+            assert!(!name.contains(".."));
+        }
+    }
+
+    fn playing_state(now: Instant) -> MprisState {
+        MprisState {
+            playback_info: Some(PlaybackInfo {
+                status: PlaybackStatus::Playing,
+                position: 0.0,
+                shuffle: false,
+                repeat_mode: RepeatMode::None,
+                playback_rate: 1.0,
+                volume: 1.0,
+            }),
+            position_updated: Some(now),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn pausing_keeps_the_extrapolated_position() {
+        let start = Instant::now();
+        let later = start + Duration::from_secs(60);
+        let mut state = playing_state(start);
+
+        let (info, seeked) = state
+            .update_playback_info(later, |info| info.status = PlaybackStatus::Paused)
+            .unwrap();
+        assert!(!seeked);
+        assert_eq!(info.position, 60.0);
+        assert_eq!(state.position_updated, Some(later));
+        // Paused playback no longer advances
+        assert_eq!(state.position_at(later + Duration::from_secs(30)), 60.0);
+    }
+
+    #[test]
+    fn reports_close_to_the_extrapolated_position_are_not_seeks() {
+        let start = Instant::now();
+        let mut state = playing_state(start);
+        let mut info = state.playback_info.clone().unwrap();
+        info.position = 60.5;
+        assert!(!state.report_playback_info(info, start + Duration::from_secs(60)));
+    }
+
+    #[test]
+    fn reports_far_from_the_extrapolated_position_are_seeks() {
+        let start = Instant::now();
+        let mut state = playing_state(start);
+        let info = state.playback_info.clone().unwrap();
+        assert!(state.report_playback_info(info, start + Duration::from_secs(60)));
+
+        let (info, seeked) = state
+            .update_playback_info(start + Duration::from_secs(61), |info| info.position = 10.0)
+            .unwrap();
+        assert!(seeked);
+        assert_eq!(info.position, 10.0);
+    }
+
+    #[test]
+    fn first_report_is_never_a_seek() {
+        let mut state = MprisState::default();
+        let info = playing_state(Instant::now()).playback_info.unwrap();
+        assert!(state.update_playback_info(Instant::now(), |_| ()).is_none());
+        assert!(!state.report_playback_info(
+            PlaybackInfo {
+                position: 120.0,
+                ..info
+            },
+            Instant::now()
+        ));
+    }
+
+    #[test]
+    fn extrapolation_stops_at_the_track_duration() {
+        let start = Instant::now();
+        let mut state = playing_state(start);
+        state.metadata = Some(MediaMetadata {
+            duration: Some(30.0),
+            ..Default::default()
+        });
+        assert_eq!(state.position_at(start + Duration::from_secs(60)), 30.0);
+    }
 }
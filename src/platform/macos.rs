@@ -1,5 +1,4 @@
 use crate::models::*;
-use std::error::Error as StdError;
 
 #[cfg(target_os = "macos")]
 use cocoa::base::{id, nil};
@@ -14,6 +13,9 @@ pub struct MacOSMediaController {
     event_handler: Option<Box<dyn Fn(MediaControlEvent) + Send>>,
     metadata: Option<MediaMetadata>,
     playback_info: Option<PlaybackInfo>,
+    tracks: Vec<Track>,
+    capabilities: MediaCapabilities,
+    initialized: bool,
 }
 
 impl MacOSMediaController {
@@ -22,6 +24,9 @@ impl MacOSMediaController {
             event_handler: None,
             metadata: None,
             playback_info: None,
+            tracks: Vec::new(),
+            capabilities: MediaCapabilities::default(),
+            initialized: false,
         }
     }
 
@@ -44,37 +49,42 @@ impl MacOSMediaController {
     }
 
     #[cfg(target_os = "macos")]
-    fn setup_command_handlers(&mut self) -> Result<(), Box<dyn StdError>> {
+    fn setup_command_handlers(&mut self) -> crate::Result<()> {
         unsafe {
             let command_center = Self::get_command_center();
 
+            let caps = &self.capabilities;
+
             // Play command
             let play_command: id = msg_send![command_center, playCommand];
-            let _: () = msg_send![play_command, setEnabled: true];
+            let _: () = msg_send![play_command, setEnabled: caps.can_control && caps.can_play];
 
             // Pause command
             let pause_command: id = msg_send![command_center, pauseCommand];
-            let _: () = msg_send![pause_command, setEnabled: true];
+            let _: () = msg_send![pause_command, setEnabled: caps.can_control && caps.can_pause];
 
             // Stop command
             let stop_command: id = msg_send![command_center, stopCommand];
-            let _: () = msg_send![stop_command, setEnabled: true];
+            let _: () = msg_send![stop_command, setEnabled: caps.can_control];
 
             // Next track command
             let next_command: id = msg_send![command_center, nextTrackCommand];
-            let _: () = msg_send![next_command, setEnabled: true];
+            let _: () = msg_send![next_command, setEnabled: caps.can_control && caps.can_go_next];
 
             // Previous track command
             let previous_command: id = msg_send![command_center, previousTrackCommand];
-            let _: () = msg_send![previous_command, setEnabled: true];
+            let _: () = msg_send![
+                previous_command,
+                setEnabled: caps.can_control && caps.can_go_previous
+            ];
 
             // Toggle play/pause command
             let toggle_command: id = msg_send![command_center, togglePlayPauseCommand];
-            let _: () = msg_send![toggle_command, setEnabled: true];
+            let _: () = msg_send![toggle_command, setEnabled: caps.can_control && caps.can_pause];
 
             // Change playback position command
             let position_command: id = msg_send![command_center, changePlaybackPositionCommand];
-            let _: () = msg_send![position_command, setEnabled: true];
+            let _: () = msg_send![position_command, setEnabled: caps.can_control && caps.can_seek];
         }
 
         Ok(())
@@ -84,17 +94,17 @@ impl MacOSMediaController {
 impl super::MediaController for MacOSMediaController {
     fn initialize_session(
         &mut self,
-        _app_id: String,
-        _app_name: String,
-    ) -> Result<(), Box<dyn StdError>> {
+        _request: InitializeMediaSessionRequest,
+    ) -> crate::Result<MediaSessionInfo> {
         #[cfg(target_os = "macos")]
         {
             self.setup_command_handlers()?;
         }
-        Ok(())
+        self.initialized = true;
+        Ok(MediaSessionInfo::default())
     }
 
-    fn set_metadata(&mut self, metadata: MediaMetadata) -> Result<(), Box<dyn StdError>> {
+    fn set_metadata(&mut self, metadata: MediaMetadata) -> crate::Result<()> {
         #[cfg(target_os = "macos")]
         {
             unsafe {
@@ -107,9 +117,10 @@ impl super::MediaController for MacOSMediaController {
                 info.push((title_key, title_value));
 
                 // Artist
-                if let Some(artist) = &metadata.artist {
+                let artists = metadata.all_artists();
+                if !artists.is_empty() {
                     let artist_key = NSString::alloc(nil).init_str("MPMediaItemPropertyArtist");
-                    let artist_value = NSString::alloc(nil).init_str(artist);
+                    let artist_value = NSString::alloc(nil).init_str(&artists.join(", "));
                     info.push((artist_key, artist_value));
                 }
 
@@ -121,13 +132,44 @@ impl super::MediaController for MacOSMediaController {
                 }
 
                 // Album Artist
-                if let Some(album_artist) = &metadata.album_artist {
+                let album_artists = metadata.all_album_artists();
+                if !album_artists.is_empty() {
                     let album_artist_key =
                         NSString::alloc(nil).init_str("MPMediaItemPropertyAlbumArtist");
-                    let album_artist_value = NSString::alloc(nil).init_str(album_artist);
+                    let album_artist_value =
+                        NSString::alloc(nil).init_str(&album_artists.join(", "));
                     info.push((album_artist_key, album_artist_value));
                 }
 
+                // Genre
+                if !metadata.genres.is_empty() {
+                    let genre_key = NSString::alloc(nil).init_str("MPMediaItemPropertyGenre");
+                    let genre_value = NSString::alloc(nil).init_str(&metadata.genres.join(", "));
+                    info.push((genre_key, genre_value));
+                }
+
+                // Composer
+                if !metadata.composers.is_empty() {
+                    let composer_key = NSString::alloc(nil).init_str("MPMediaItemPropertyComposer");
+                    let composer_value =
+                        NSString::alloc(nil).init_str(&metadata.composers.join(", "));
+                    info.push((composer_key, composer_value));
+                }
+
+                // Track and disc numbers
+                if let Some(track_number) = metadata.track_number {
+                    let track_key =
+                        NSString::alloc(nil).init_str("MPMediaItemPropertyAlbumTrackNumber");
+                    let track_value = NSNumber::alloc(nil).init_f64(track_number as f64);
+                    info.push((track_key, track_value));
+                }
+
+                if let Some(disc_number) = metadata.disc_number {
+                    let disc_key = NSString::alloc(nil).init_str("MPMediaItemPropertyDiscNumber");
+                    let disc_value = NSNumber::alloc(nil).init_f64(disc_number as f64);
+                    info.push((disc_key, disc_value));
+                }
+
                 // Duration
                 if let Some(duration) = metadata.duration {
                     let duration_key =
@@ -180,7 +222,7 @@ impl super::MediaController for MacOSMediaController {
         Ok(())
     }
 
-    fn set_playback_info(&mut self, info: PlaybackInfo) -> Result<(), Box<dyn StdError>> {
+    fn set_playback_info(&mut self, info: PlaybackInfo) -> crate::Result<()> {
         #[cfg(target_os = "macos")]
         {
             unsafe {
@@ -220,7 +262,7 @@ impl super::MediaController for MacOSMediaController {
         Ok(())
     }
 
-    fn set_playback_status(&mut self, status: PlaybackStatus) -> Result<(), Box<dyn StdError>> {
+    fn set_playback_status(&mut self, status: PlaybackStatus) -> crate::Result<()> {
         #[cfg(target_os = "macos")]
         {
             unsafe {
@@ -259,7 +301,7 @@ impl super::MediaController for MacOSMediaController {
         Ok(())
     }
 
-    fn set_position(&mut self, position: f64) -> Result<(), Box<dyn StdError>> {
+    fn set_position(&mut self, position: f64) -> crate::Result<()> {
         #[cfg(target_os = "macos")]
         {
             unsafe {
@@ -293,7 +335,7 @@ impl super::MediaController for MacOSMediaController {
         Ok(())
     }
 
-    fn clear_metadata(&mut self) -> Result<(), Box<dyn StdError>> {
+    fn clear_metadata(&mut self) -> crate::Result<()> {
         #[cfg(target_os = "macos")]
         {
             unsafe {
@@ -316,10 +358,77 @@ impl super::MediaController for MacOSMediaController {
         // This is a simplified version. In production, you'd need to create proper target-action pairs
     }
 
-    fn get_metadata(&self) -> Result<Option<MediaMetadata>, Box<dyn StdError>> {
+    fn set_capabilities(&mut self, capabilities: MediaCapabilities) -> crate::Result<()> {
+        self.capabilities = capabilities;
+
+        #[cfg(target_os = "macos")]
+        {
+            self.setup_command_handlers()?;
+        }
+
+        Ok(())
+    }
+
+    fn set_track_list(&mut self, tracks: Vec<Track>) -> crate::Result<()> {
+        self.tracks = tracks;
+        Ok(())
+    }
+
+    fn add_track(&mut self, track: Track, after_track_id: Option<String>) -> crate::Result<()> {
+        super::insert_track(&mut self.tracks, track, after_track_id.as_deref());
+        Ok(())
+    }
+
+    fn remove_track(&mut self, track_id: String) -> crate::Result<()> {
+        self.tracks.retain(|t| t.id != track_id);
+        Ok(())
+    }
+
+    fn update_track_metadata(
+        &mut self,
+        track_id: String,
+        metadata: MediaMetadata,
+    ) -> crate::Result<()> {
+        super::update_track_metadata(&mut self.tracks, &track_id, metadata);
+        Ok(())
+    }
+
+    fn set_playlists(
+        &mut self,
+        _playlists: Vec<Playlist>,
+        _active_playlist_id: Option<String>,
+    ) -> crate::Result<()> {
+        // There is no native surface for playlists on this platform
+        Ok(())
+    }
+
+    fn list_sessions(&self) -> crate::Result<Vec<SystemMediaSession>> {
+        // Enumerating other applications' sessions is only supported through MPRIS
+        Ok(Vec::new())
+    }
+
+    fn control_session(
+        &self,
+        _session_id: Option<String>,
+        _action: SessionAction,
+    ) -> crate::Result<()> {
+        Err(crate::Error::Unsupported {
+            feature: "control_session".to_string(),
+        })
+    }
+
+    fn watch_sessions(&mut self, _handler: Box<dyn Fn(SessionEvent) + Send>) -> crate::Result<()> {
+        Err(crate::Error::Unsupported {
+            feature: "watch_sessions".to_string(),
+        })
+    }
+
+    fn unwatch_sessions(&mut self) {}
+
+    fn get_metadata(&self) -> crate::Result<Option<MediaMetadata>> {
         #[cfg(target_os = "macos")]
         unsafe {
-            // Get current now playing info from the system
+            // Read back the now playing info this application published
             let info_center = Self::get_info_center();
             let now_playing_info: id = msg_send![info_center, nowPlayingInfo];
 
@@ -335,12 +444,7 @@ impl super::MediaController for MacOSMediaController {
 
                     let mut metadata = MediaMetadata {
                         title: title_str,
-                        artist: None,
-                        album: None,
-                        album_artist: None,
-                        duration: None,
-                        artwork_url: None,
-                        artwork_data: None,
+                        ..Default::default()
                     };
 
                     // Get artist
@@ -395,11 +499,11 @@ impl super::MediaController for MacOSMediaController {
         Ok(self.metadata.clone())
     }
 
-    fn get_playback_info(&self) -> Result<Option<PlaybackInfo>, Box<dyn StdError>> {
+    fn get_playback_info(&self) -> crate::Result<Option<PlaybackInfo>> {
         Ok(self.playback_info.clone())
     }
 
-    fn get_playback_status(&self) -> Result<PlaybackStatus, Box<dyn StdError>> {
+    fn get_playback_status(&self) -> crate::Result<PlaybackStatus> {
         Ok(self
             .playback_info
             .as_ref()
@@ -407,7 +511,7 @@ impl super::MediaController for MacOSMediaController {
             .unwrap_or(PlaybackStatus::Stopped))
     }
 
-    fn get_position(&self) -> Result<f64, Box<dyn StdError>> {
+    fn get_position(&self) -> crate::Result<f64> {
         Ok(self
             .playback_info
             .as_ref()
@@ -415,8 +519,29 @@ impl super::MediaController for MacOSMediaController {
             .unwrap_or(0.0))
     }
 
-    fn is_enabled(&self) -> Result<bool, Box<dyn StdError>> {
+    fn is_enabled(&self) -> crate::Result<bool> {
         // On macOS, media controls are always available once initialized
         Ok(self.initialized)
     }
+
+    fn get_track_list(&self) -> crate::Result<Vec<Track>> {
+        Ok(self.tracks.clone())
+    }
+
+    // macOS offers no public API to read other applications' now playing state
+    fn get_system_metadata(&self) -> crate::Result<Option<MediaMetadata>> {
+        Ok(None)
+    }
+
+    fn get_system_playback_info(&self) -> crate::Result<Option<PlaybackInfo>> {
+        Ok(None)
+    }
+
+    fn get_system_playback_status(&self) -> crate::Result<PlaybackStatus> {
+        Ok(PlaybackStatus::Stopped)
+    }
+
+    fn get_system_position(&self) -> crate::Result<f64> {
+        Ok(0.0)
+    }
 }
@@ -1,6 +1,10 @@
 use crate::models::*;
-use std::error::Error as StdError;
+use std::path::PathBuf;
 
+#[cfg(target_os = "linux")]
+mod activity;
+#[cfg(target_os = "linux")]
+mod artwork;
 #[cfg(target_os = "linux")]
 mod linux;
 #[cfg(target_os = "macos")]
@@ -11,25 +15,138 @@ mod windows;
 pub trait MediaController {
     fn initialize_session(
         &mut self,
-        app_id: String,
-        app_name: String,
-    ) -> Result<(), Box<dyn StdError>>;
-    fn set_metadata(&mut self, metadata: MediaMetadata) -> Result<(), Box<dyn StdError>>;
-    fn set_playback_info(&mut self, info: PlaybackInfo) -> Result<(), Box<dyn StdError>>;
-    fn set_playback_status(&mut self, status: PlaybackStatus) -> Result<(), Box<dyn StdError>>;
-    fn set_position(&mut self, position: f64) -> Result<(), Box<dyn StdError>>;
-    fn clear_metadata(&mut self) -> Result<(), Box<dyn StdError>>;
+        request: InitializeMediaSessionRequest,
+    ) -> crate::Result<MediaSessionInfo>;
+    fn set_metadata(&mut self, metadata: MediaMetadata) -> crate::Result<()>;
+    fn set_playback_info(&mut self, info: PlaybackInfo) -> crate::Result<()>;
+    fn set_playback_status(&mut self, status: PlaybackStatus) -> crate::Result<()>;
+    fn set_position(&mut self, position: f64) -> crate::Result<()>;
+    fn clear_metadata(&mut self) -> crate::Result<()>;
     fn set_event_handler(&mut self, handler: Box<dyn Fn(MediaControlEvent) + Send>);
+    fn set_capabilities(&mut self, capabilities: MediaCapabilities) -> crate::Result<()>;
+
+    // Track list management
+    fn set_track_list(&mut self, tracks: Vec<Track>) -> crate::Result<()>;
+    fn add_track(&mut self, track: Track, after_track_id: Option<String>) -> crate::Result<()>;
+    fn remove_track(&mut self, track_id: String) -> crate::Result<()>;
+    fn update_track_metadata(
+        &mut self,
+        track_id: String,
+        metadata: MediaMetadata,
+    ) -> crate::Result<()>;
+
+    // Playlists
+    fn set_playlists(
+        &mut self,
+        playlists: Vec<Playlist>,
+        active_playlist_id: Option<String>,
+    ) -> crate::Result<()>;
+
+    // System sessions
+    fn list_sessions(&self) -> crate::Result<Vec<SystemMediaSession>>;
+    fn control_session(
+        &self,
+        session_id: Option<String>, // None targets the active session
+        action: SessionAction,
+    ) -> crate::Result<()>;
+    fn watch_sessions(&mut self, handler: Box<dyn Fn(SessionEvent) + Send>) -> crate::Result<()>;
+    fn unwatch_sessions(&mut self);
+
+    // Get methods to retrieve this application's own session state
+    fn get_metadata(&self) -> crate::Result<Option<MediaMetadata>>;
+    fn get_playback_info(&self) -> crate::Result<Option<PlaybackInfo>>;
+    fn get_playback_status(&self) -> crate::Result<PlaybackStatus>;
+    fn get_position(&self) -> crate::Result<f64>;
+    fn is_enabled(&self) -> crate::Result<bool>;
+    fn get_track_list(&self) -> crate::Result<Vec<Track>>;
+
+    // Get methods for the system's active session, which may belong to another application
+    fn get_system_metadata(&self) -> crate::Result<Option<MediaMetadata>>;
+    fn get_system_playback_info(&self) -> crate::Result<Option<PlaybackInfo>>;
+    fn get_system_playback_status(&self) -> crate::Result<PlaybackStatus>;
+    fn get_system_position(&self) -> crate::Result<f64>;
+
+    /// Withdraws what the session published: playback becomes `Stopped` and
+    /// the metadata is cleared. Platforms with per-session resources, such as
+    /// a bus name, release them too. The session can be initialized again.
+    fn end_session(&mut self) -> crate::Result<()> {
+        if !self.is_enabled()? {
+            return Ok(());
+        }
+        self.set_playback_status(PlaybackStatus::Stopped)?;
+        self.clear_metadata()
+    }
+}
 
-    // Get methods to retrieve current state
-    fn get_metadata(&self) -> Result<Option<MediaMetadata>, Box<dyn StdError>>;
-    fn get_playback_info(&self) -> Result<Option<PlaybackInfo>, Box<dyn StdError>>;
-    fn get_playback_status(&self) -> Result<PlaybackStatus, Box<dyn StdError>>;
-    fn get_position(&self) -> Result<f64, Box<dyn StdError>>;
-    fn is_enabled(&self) -> Result<bool, Box<dyn StdError>>;
+/// Inserts `track` after the track with id `after_track_id`, or at the start
+/// of the list when it is `None`. An unknown id appends to the end. A track
+/// with the same id as `track` is replaced.
+pub(crate) fn insert_track(tracks: &mut Vec<Track>, track: Track, after_track_id: Option<&str>) {
+    tracks.retain(|t| t.id != track.id);
+
+    let index = match after_track_id {
+        None => 0,
+        Some(after) => tracks
+            .iter()
+            .position(|t| t.id == after)
+            .map(|i| i + 1)
+            .unwrap_or(tracks.len()),
+    };
+    tracks.insert(index, track);
+}
+
+/// Replaces the metadata of the track with id `track_id`. Returns whether a
+/// track was found.
+pub(crate) fn update_track_metadata(
+    tracks: &mut [Track],
+    track_id: &str,
+    metadata: MediaMetadata,
+) -> bool {
+    match tracks.iter_mut().find(|t| t.id == track_id) {
+        Some(track) => {
+            track.metadata = metadata;
+            true
+        }
+        None => false,
+    }
+}
+
+/// Whether an application may publish more than one session at a time. On
+/// Linux every session is its own MPRIS player. Windows and macOS have a
+/// single now-playing surface per process, so only the default session exists.
+pub const MULTIPLE_SESSIONS: bool = cfg!(target_os = "linux");
+
+/// Platform state shared by the controllers of all of an application's
+/// sessions, created once when the plugin is set up.
+#[derive(Clone)]
+pub struct PlatformContext {
+    #[cfg(target_os = "linux")]
+    players: std::sync::Arc<linux::PlayerTracker>,
+}
+
+impl PlatformContext {
+    pub fn new(config: &Config) -> Self {
+        #[cfg(not(target_os = "linux"))]
+        let _ = config;
+        PlatformContext {
+            #[cfg(target_os = "linux")]
+            players: std::sync::Arc::new(linux::PlayerTracker::new(config)),
+        }
+    }
 }
 
-pub fn create_media_controller() -> Box<dyn MediaController + Send> {
+/// Creates the controller for the current platform. `artwork_dir` is where
+/// raw artwork may be stored for platforms that can only reference images by
+/// URL. `instance` is `None` for the application's default session and the
+/// session id for additional ones.
+pub fn create_media_controller(
+    config: &Config,
+    context: &PlatformContext,
+    artwork_dir: PathBuf,
+    instance: Option<&str>,
+) -> Box<dyn MediaController + Send> {
+    #[cfg(not(target_os = "linux"))]
+    let _ = (config, context, artwork_dir, instance);
     #[cfg(target_os = "windows")]
     {
         Box::new(windows::WindowsMediaController::new())
@@ -40,6 +157,11 @@ pub fn create_media_controller() -> Box<dyn MediaController + Send> {
     }
     #[cfg(target_os = "linux")]
     {
-        Box::new(linux::LinuxMediaController::new())
+        Box::new(linux::LinuxMediaController::new(
+            config,
+            context.players.clone(),
+            artwork_dir,
+            instance,
+        ))
     }
 }
@@ -1,5 +1,4 @@
 use crate::models::*;
-use std::error::Error as StdError;
 use std::sync::{Arc, Mutex};
 
 #[cfg(target_os = "windows")]
@@ -27,6 +26,8 @@ pub struct WindowsMediaController {
     event_handler: Option<Arc<Mutex<Box<dyn Fn(MediaControlEvent) + Send>>>>,
     metadata: Option<MediaMetadata>,
     playback_info: Option<PlaybackInfo>,
+    tracks: Vec<Track>,
+    capabilities: MediaCapabilities,
 }
 
 impl WindowsMediaController {
@@ -38,12 +39,54 @@ impl WindowsMediaController {
             event_handler: None,
             metadata: None,
             playback_info: None,
+            tracks: Vec::new(),
+            capabilities: MediaCapabilities::default(),
         }
     }
 
     #[cfg(target_os = "windows")]
-    fn get_global_session(
-    ) -> Result<Option<GlobalSystemMediaTransportControlsSession>, Box<dyn StdError>> {
+    fn apply_capabilities(&self) -> crate::Result<()> {
+        let controls = self.controls.as_ref().ok_or(crate::Error::NotInitialized)?;
+        let caps = &self.capabilities;
+        // SMTC has no seek switch; seeking is limited through the timeline instead
+        controls.SetIsPlayEnabled(caps.can_control && caps.can_play)?;
+        controls.SetIsPauseEnabled(caps.can_control && caps.can_pause)?;
+        controls.SetIsNextEnabled(caps.can_control && caps.can_go_next)?;
+        controls.SetIsPreviousEnabled(caps.can_control && caps.can_go_previous)?;
+        controls.SetIsStopEnabled(caps.can_control)?;
+        Ok(())
+    }
+
+    /// Publishes the timeline of the current track, if its duration is known.
+    /// SMTC only lets users scrub between `MinSeekTime` and `MaxSeekTime`, so
+    /// both are pinned to the current position when seeking is unavailable.
+    #[cfg(target_os = "windows")]
+    fn update_timeline(&mut self, position: f64) -> crate::Result<()> {
+        let Some(duration) = self.metadata.as_ref().and_then(|m| m.duration) else {
+            return Ok(());
+        };
+        let can_seek = self.capabilities.can_control && self.capabilities.can_seek;
+        let (min_seek, max_seek) = if can_seek {
+            (0.0, duration)
+        } else {
+            (position, position)
+        };
+        let span = |seconds: f64| windows::Foundation::TimeSpan {
+            Duration: (seconds * 10_000_000.0) as i64,
+        };
+
+        let timeline = SystemMediaTransportControlsTimelineProperties::new()?;
+        timeline.SetStartTime(span(0.0))?;
+        timeline.SetEndTime(span(duration))?;
+        timeline.SetPosition(span(position))?;
+        timeline.SetMinSeekTime(span(min_seek))?;
+        timeline.SetMaxSeekTime(span(max_seek))?;
+        self.get_controls()?.UpdateTimelineProperties(&timeline)?;
+        Ok(())
+    }
+
+    #[cfg(target_os = "windows")]
+    fn get_global_session() -> crate::Result<Option<GlobalSystemMediaTransportControlsSession>> {
         // RequestAsync returns an IAsyncOperation, we need to get it synchronously
         let async_op = GlobalSystemMediaTransportControlsSessionManager::RequestAsync()?;
 
@@ -61,8 +104,7 @@ impl WindowsMediaController {
 
     #[cfg(target_os = "windows")]
     #[allow(dead_code)]
-    fn get_all_sessions(
-    ) -> Result<Vec<GlobalSystemMediaTransportControlsSession>, Box<dyn StdError>> {
+    fn get_all_sessions() -> crate::Result<Vec<GlobalSystemMediaTransportControlsSession>> {
         let async_op = GlobalSystemMediaTransportControlsSessionManager::RequestAsync()?;
         let session_manager = async_op.get()?;
 
@@ -79,7 +121,7 @@ impl WindowsMediaController {
     }
 
     #[cfg(target_os = "windows")]
-    fn get_controls(&mut self) -> Result<&SystemMediaTransportControls, Box<dyn StdError>> {
+    fn get_controls(&mut self) -> crate::Result<&SystemMediaTransportControls> {
         if self.controls.is_none() {
             // Create a MediaPlayer instance first
             if self.media_player.is_none() {
@@ -98,11 +140,11 @@ impl WindowsMediaController {
         }
         self.controls
             .as_ref()
-            .ok_or("Failed to get controls".into())
+            .ok_or_else(|| crate::Error::platform("failed to get controls"))
     }
 
     #[cfg(target_os = "windows")]
-    fn setup_button_handlers(&mut self) -> Result<(), Box<dyn StdError>> {
+    fn setup_button_handlers(&mut self) -> crate::Result<()> {
         // Clone event handler before getting controls to avoid borrow issues
         let handler = self.event_handler.clone();
         let controls = self.get_controls()?;
@@ -115,41 +157,21 @@ impl WindowsMediaController {
                     if let Some(args) = args {
                         let button = args.Button()?;
                         let event = match button {
-                            SystemMediaTransportControlsButton::Play => MediaControlEvent {
-                                event_type: MediaControlEventType::Play,
-                                timestamp: std::time::SystemTime::now()
-                                    .duration_since(std::time::UNIX_EPOCH)
-                                    .unwrap()
-                                    .as_secs(),
-                            },
-                            SystemMediaTransportControlsButton::Pause => MediaControlEvent {
-                                event_type: MediaControlEventType::Pause,
-                                timestamp: std::time::SystemTime::now()
-                                    .duration_since(std::time::UNIX_EPOCH)
-                                    .unwrap()
-                                    .as_secs(),
-                            },
-                            SystemMediaTransportControlsButton::Stop => MediaControlEvent {
-                                event_type: MediaControlEventType::Stop,
-                                timestamp: std::time::SystemTime::now()
-                                    .duration_since(std::time::UNIX_EPOCH)
-                                    .unwrap()
-                                    .as_secs(),
-                            },
-                            SystemMediaTransportControlsButton::Next => MediaControlEvent {
-                                event_type: MediaControlEventType::Next,
-                                timestamp: std::time::SystemTime::now()
-                                    .duration_since(std::time::UNIX_EPOCH)
-                                    .unwrap()
-                                    .as_secs(),
-                            },
-                            SystemMediaTransportControlsButton::Previous => MediaControlEvent {
-                                event_type: MediaControlEventType::Previous,
-                                timestamp: std::time::SystemTime::now()
-                                    .duration_since(std::time::UNIX_EPOCH)
-                                    .unwrap()
-                                    .as_secs(),
-                            },
+                            SystemMediaTransportControlsButton::Play => {
+                                MediaControlEvent::new(MediaControlEventType::Play)
+                            }
+                            SystemMediaTransportControlsButton::Pause => {
+                                MediaControlEvent::new(MediaControlEventType::Pause)
+                            }
+                            SystemMediaTransportControlsButton::Stop => {
+                                MediaControlEvent::new(MediaControlEventType::Stop)
+                            }
+                            SystemMediaTransportControlsButton::Next => {
+                                MediaControlEvent::new(MediaControlEventType::Next)
+                            }
+                            SystemMediaTransportControlsButton::Previous => {
+                                MediaControlEvent::new(MediaControlEventType::Previous)
+                            }
                             _ => return Ok(()),
                         };
 
@@ -169,25 +191,20 @@ impl WindowsMediaController {
 impl super::MediaController for WindowsMediaController {
     fn initialize_session(
         &mut self,
-        _app_id: String,
-        _app_name: String,
-    ) -> Result<(), Box<dyn StdError>> {
+        _request: InitializeMediaSessionRequest,
+    ) -> crate::Result<MediaSessionInfo> {
         #[cfg(target_os = "windows")]
         {
             let controls = self.get_controls()?;
             controls.SetIsEnabled(true)?;
-            controls.SetIsPlayEnabled(true)?;
-            controls.SetIsPauseEnabled(true)?;
-            controls.SetIsNextEnabled(true)?;
-            controls.SetIsPreviousEnabled(true)?;
-            controls.SetIsStopEnabled(true)?;
+            self.apply_capabilities()?;
 
             self.setup_button_handlers()?;
         }
-        Ok(())
+        Ok(MediaSessionInfo::default())
     }
 
-    fn set_metadata(&mut self, metadata: MediaMetadata) -> Result<(), Box<dyn StdError>> {
+    fn set_metadata(&mut self, metadata: MediaMetadata) -> crate::Result<()> {
         #[cfg(target_os = "windows")]
         {
             let controls = self.get_controls()?;
@@ -198,14 +215,30 @@ impl super::MediaController for WindowsMediaController {
             let props = updater.MusicProperties()?;
             props.SetTitle(&windows::core::HSTRING::from(&metadata.title))?;
 
-            if let Some(artist) = &metadata.artist {
-                props.SetArtist(&windows::core::HSTRING::from(artist))?;
+            let artists = metadata.all_artists();
+            if !artists.is_empty() {
+                props.SetArtist(&windows::core::HSTRING::from(artists.join(", ")))?;
             }
 
             if let Some(album) = &metadata.album {
                 props.SetAlbumTitle(&windows::core::HSTRING::from(album))?;
             }
 
+            let album_artists = metadata.all_album_artists();
+            if !album_artists.is_empty() {
+                props.SetAlbumArtist(&windows::core::HSTRING::from(album_artists.join(", ")))?;
+            }
+
+            if let Some(track_number) = metadata.track_number {
+                props.SetTrackNumber(track_number)?;
+            }
+
+            let genres = props.Genres()?;
+            genres.Clear()?;
+            for genre in &metadata.genres {
+                genres.Append(&windows::core::HSTRING::from(genre))?;
+            }
+
             // Set artwork from URL or raw data
             if let Some(artwork_url) = &metadata.artwork_url {
                 if let Ok(uri) =
@@ -241,11 +274,9 @@ impl super::MediaController for WindowsMediaController {
         Ok(())
     }
 
-    fn set_playback_info(&mut self, info: PlaybackInfo) -> Result<(), Box<dyn StdError>> {
+    fn set_playback_info(&mut self, info: PlaybackInfo) -> crate::Result<()> {
         #[cfg(target_os = "windows")]
         {
-            // Clone metadata to avoid borrow issues
-            let metadata = self.metadata.clone();
             let controls = self.get_controls()?;
 
             // Set playback status
@@ -267,20 +298,7 @@ impl super::MediaController for WindowsMediaController {
             controls.SetShuffleEnabled(info.shuffle)?;
             controls.SetPlaybackRate(info.playback_rate)?;
 
-            // Update timeline if we have duration
-            if let Some(metadata) = metadata {
-                if let Some(duration) = metadata.duration {
-                    let timeline = SystemMediaTransportControlsTimelineProperties::new()?;
-                    timeline.SetStartTime(windows::Foundation::TimeSpan { Duration: 0 })?;
-                    timeline.SetEndTime(windows::Foundation::TimeSpan {
-                        Duration: (duration * 10_000_000.0) as i64,
-                    })?;
-                    timeline.SetPosition(windows::Foundation::TimeSpan {
-                        Duration: (info.position * 10_000_000.0) as i64,
-                    })?;
-                    controls.UpdateTimelineProperties(&timeline)?;
-                }
-            }
+            self.update_timeline(info.position)?;
 
             self.playback_info = Some(info);
         }
@@ -291,7 +309,7 @@ impl super::MediaController for WindowsMediaController {
         Ok(())
     }
 
-    fn set_playback_status(&mut self, status: PlaybackStatus) -> Result<(), Box<dyn StdError>> {
+    fn set_playback_status(&mut self, status: PlaybackStatus) -> crate::Result<()> {
         #[cfg(target_os = "windows")]
         {
             let controls = self.get_controls()?;
@@ -317,23 +335,10 @@ impl super::MediaController for WindowsMediaController {
         Ok(())
     }
 
-    fn set_position(&mut self, position: f64) -> Result<(), Box<dyn StdError>> {
+    fn set_position(&mut self, position: f64) -> crate::Result<()> {
         #[cfg(target_os = "windows")]
         {
-            if let Some(metadata) = &self.metadata {
-                if let Some(duration) = metadata.duration {
-                    let controls = self.get_controls()?;
-                    let timeline = SystemMediaTransportControlsTimelineProperties::new()?;
-                    timeline.SetStartTime(windows::Foundation::TimeSpan { Duration: 0 })?;
-                    timeline.SetEndTime(windows::Foundation::TimeSpan {
-                        Duration: (duration * 10_000_000.0) as i64,
-                    })?;
-                    timeline.SetPosition(windows::Foundation::TimeSpan {
-                        Duration: (position * 10_000_000.0) as i64,
-                    })?;
-                    controls.UpdateTimelineProperties(&timeline)?;
-                }
-            }
+            self.update_timeline(position)?;
 
             if let Some(mut info) = self.playback_info.clone() {
                 info.position = position;
@@ -350,7 +355,7 @@ impl super::MediaController for WindowsMediaController {
         Ok(())
     }
 
-    fn clear_metadata(&mut self) -> Result<(), Box<dyn StdError>> {
+    fn clear_metadata(&mut self) -> crate::Result<()> {
         #[cfg(target_os = "windows")]
         {
             let controls = self.get_controls()?;
@@ -374,10 +379,105 @@ impl super::MediaController for WindowsMediaController {
         }
     }
 
-    fn get_metadata(&self) -> Result<Option<MediaMetadata>, Box<dyn StdError>> {
+    fn set_capabilities(&mut self, capabilities: MediaCapabilities) -> crate::Result<()> {
+        self.capabilities = capabilities;
+
+        #[cfg(target_os = "windows")]
+        {
+            if self.controls.is_some() {
+                self.apply_capabilities()?;
+                if let Some(position) = self.playback_info.as_ref().map(|info| info.position) {
+                    self.update_timeline(position)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn set_track_list(&mut self, tracks: Vec<Track>) -> crate::Result<()> {
+        self.tracks = tracks;
+        Ok(())
+    }
+
+    fn add_track(&mut self, track: Track, after_track_id: Option<String>) -> crate::Result<()> {
+        super::insert_track(&mut self.tracks, track, after_track_id.as_deref());
+        Ok(())
+    }
+
+    fn remove_track(&mut self, track_id: String) -> crate::Result<()> {
+        self.tracks.retain(|t| t.id != track_id);
+        Ok(())
+    }
+
+    fn update_track_metadata(
+        &mut self,
+        track_id: String,
+        metadata: MediaMetadata,
+    ) -> crate::Result<()> {
+        super::update_track_metadata(&mut self.tracks, &track_id, metadata);
+        Ok(())
+    }
+
+    fn set_playlists(
+        &mut self,
+        _playlists: Vec<Playlist>,
+        _active_playlist_id: Option<String>,
+    ) -> crate::Result<()> {
+        // There is no native surface for playlists on this platform
+        Ok(())
+    }
+
+    fn list_sessions(&self) -> crate::Result<Vec<SystemMediaSession>> {
+        // Enumerating other applications' sessions is only supported through MPRIS
+        Ok(Vec::new())
+    }
+
+    fn control_session(
+        &self,
+        _session_id: Option<String>,
+        _action: SessionAction,
+    ) -> crate::Result<()> {
+        Err(crate::Error::Unsupported {
+            feature: "control_session".to_string(),
+        })
+    }
+
+    fn watch_sessions(&mut self, _handler: Box<dyn Fn(SessionEvent) + Send>) -> crate::Result<()> {
+        Err(crate::Error::Unsupported {
+            feature: "watch_sessions".to_string(),
+        })
+    }
+
+    fn unwatch_sessions(&mut self) {}
+
+    fn get_metadata(&self) -> crate::Result<Option<MediaMetadata>> {
+        Ok(self.metadata.clone())
+    }
+
+    fn get_playback_info(&self) -> crate::Result<Option<PlaybackInfo>> {
+        Ok(self.playback_info.clone())
+    }
+
+    fn get_playback_status(&self) -> crate::Result<PlaybackStatus> {
+        Ok(self
+            .playback_info
+            .as_ref()
+            .map(|info| info.status)
+            .unwrap_or(PlaybackStatus::Stopped))
+    }
+
+    fn get_position(&self) -> crate::Result<f64> {
+        Ok(self
+            .playback_info
+            .as_ref()
+            .map(|info| info.position)
+            .unwrap_or(0.0))
+    }
+
+    fn get_system_metadata(&self) -> crate::Result<Option<MediaMetadata>> {
         #[cfg(target_os = "windows")]
         {
-            // Try to get system-wide media info first
             if let Ok(Some(session)) = Self::get_global_session() {
                 if let Ok(media_properties) = session.TryGetMediaPropertiesAsync()?.get() {
                     let title = media_properties.Title()?.to_string();
@@ -429,19 +529,18 @@ impl super::MediaController for WindowsMediaController {
                             artwork_url: None,
                             artwork_data,
                             duration: None,
+                            ..Default::default()
                         }));
                     }
                 }
             }
         }
-        // Fall back to our own metadata
-        Ok(self.metadata.clone())
+        Ok(None)
     }
 
-    fn get_playback_info(&self) -> Result<Option<PlaybackInfo>, Box<dyn StdError>> {
+    fn get_system_playback_info(&self) -> crate::Result<Option<PlaybackInfo>> {
         #[cfg(target_os = "windows")]
         {
-            // Try to get system-wide playback info
             if let Ok(Some(session)) = Self::get_global_session() {
                 if let Ok(playback_info) = session.GetPlaybackInfo() {
                     let status = match playback_info.PlaybackStatus()? {
@@ -472,18 +571,17 @@ impl super::MediaController for WindowsMediaController {
                         repeat_mode: repeat_mode,
                         playback_rate,
                         position: 0.0, // Will be set by get_position
+                        volume: 1.0,
                     }));
                 }
             }
         }
-        // Fall back to our own playback info
-        Ok(self.playback_info.clone())
+        Ok(None)
     }
 
-    fn get_playback_status(&self) -> Result<PlaybackStatus, Box<dyn StdError>> {
+    fn get_system_playback_status(&self) -> crate::Result<PlaybackStatus> {
         #[cfg(target_os = "windows")]
         {
-            // Try to get system-wide playback status
             if let Ok(Some(session)) = Self::get_global_session() {
                 if let Ok(playback_info) = session.GetPlaybackInfo() {
                     return Ok(match playback_info.PlaybackStatus()? {
@@ -498,18 +596,12 @@ impl super::MediaController for WindowsMediaController {
                 }
             }
         }
-        // Fall back to our own status
-        Ok(self
-            .playback_info
-            .as_ref()
-            .map(|info| info.status)
-            .unwrap_or(PlaybackStatus::Stopped))
+        Ok(PlaybackStatus::Stopped)
     }
 
-    fn get_position(&self) -> Result<f64, Box<dyn StdError>> {
+    fn get_system_position(&self) -> crate::Result<f64> {
         #[cfg(target_os = "windows")]
         {
-            // Try to get system-wide position
             if let Ok(Some(session)) = Self::get_global_session() {
                 if let Ok(timeline) = session.GetTimelineProperties() {
                     let position_ms = timeline.Position()?.Duration;
@@ -517,26 +609,20 @@ impl super::MediaController for WindowsMediaController {
                 }
             }
         }
-        // Fall back to our own position
-        Ok(self
-            .playback_info
-            .as_ref()
-            .map(|info| info.position)
-            .unwrap_or(0.0))
+        Ok(0.0)
     }
 
-    fn is_enabled(&self) -> Result<bool, Box<dyn StdError>> {
+    fn is_enabled(&self) -> crate::Result<bool> {
         #[cfg(target_os = "windows")]
         {
-            // Check if there's any active media session in the system
-            if let Ok(Some(_)) = Self::get_global_session() {
-                return Ok(true);
-            }
-            // Or if our own controls are enabled
             if let Some(ref controls) = self.controls {
                 return Ok(controls.IsEnabled().unwrap_or(false));
             }
         }
         Ok(false)
     }
+
+    fn get_track_list(&self) -> crate::Result<Vec<Track>> {
+        Ok(self.tracks.clone())
+    }
 }